@@ -1,15 +1,20 @@
 // src-tauri/src/commands/game_commands.rs
-use tauri::{AppHandle, command, Window, Emitter, Manager};
-use crate::models::{GameInfo, BackupEntry, LudusaviManifest};
+use tauri::{AppHandle, command, Window, Emitter, Manager, State};
+use crate::models::{
+    GameInfo, GameSession, BackupDiff, BackupEntry, FileRules, LudusaviManifest, RestoreOptions, RestorePlan, RestoreReport,
+    RetentionDecision, RetentionPolicy, SnapshotTreeNode, SteamAccount, StorageReport, VerifyReport, WatchSettings,
+    WatchStatus,
+};
 use crate::services::steam_service::SteamService;
 use crate::services::backup_service::BackupService;
-use crate::services::watcher_service::WatcherService;
-use crate::services::config_service::{ConfigService, AppConfig}; // Agora vai funcionar
+use crate::services::watcher_service::{WatcherService, WatcherState};
+use crate::services::session_service::{SessionService, SessionState};
+use crate::services::config_service::{ConfigService, AppConfig, AppConfigUpdate};
 use std::fs;
 
 #[command]
-pub fn get_installed_games() -> Vec<GameInfo> {
-    SteamService::list_installed_games()
+pub fn get_installed_games(app: AppHandle) -> Vec<GameInfo> {
+    SteamService::list_installed_games(&app)
 }
 
 #[command]
@@ -18,12 +23,17 @@ pub async fn backup_game(
     app: AppHandle, 
     game_id: u32, 
     game_name: String,
-    retention_limit: usize
+    retention_limit: usize,
+    label: Option<String>
 ) -> String {
     tauri::async_runtime::spawn_blocking(move || {
-        let res = BackupService::perform_backup(window.clone(), app, game_id, game_name.clone());
+        let res = BackupService::perform_backup(app.clone(), game_id, game_name, label);
         if res.starts_with("Sucesso") {
-            let _ = BackupService::cleanup_old_backups(game_name, retention_limit);
+            let policy = RetentionPolicy {
+                keep_last: retention_limit,
+                ..ConfigService::load_config(&app).retention_policy()
+            };
+            let _ = BackupService::cleanup_old_backups(&app, game_id, &policy);
             let _ = window.emit("backup-status", "Política de retenção aplicada.");
         }
         res
@@ -31,49 +41,221 @@ pub async fn backup_game(
 }
 
 #[command]
-pub fn get_backups(game_name: String) -> Vec<BackupEntry> {
-    BackupService::list_backups(game_name)
+pub fn get_steam_accounts() -> Vec<SteamAccount> {
+    SteamService::get_steam_accounts()
 }
 
 #[command]
-pub fn restore_backup(app: AppHandle, game_id: u32, game_name: String, timestamp: String) -> String {
-    BackupService::restore_backup(app, game_id, game_name, timestamp)
+pub async fn get_backups(app: AppHandle, game_id: u32) -> Result<Vec<BackupEntry>, String> {
+    // Snapshots antigos são importados para o store na primeira leitura
+    tauri::async_runtime::spawn_blocking(move || {
+        BackupService::list_backups(&app, game_id)
+    }).await.map_err(|e| e.to_string())
+}
+
+#[command]
+pub async fn browse_backup(app: AppHandle, game_id: u32, timestamp: String) -> Result<Vec<SnapshotTreeNode>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        BackupService::browse_backup(&app, game_id, timestamp)
+    }).await.map_err(|e| e.to_string())?
+}
+
+#[command]
+pub async fn restore_backup(
+    app: AppHandle,
+    game_id: u32,
+    timestamp: String,
+    options: Option<RestoreOptions>
+) -> Result<RestoreReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        BackupService::restore_backup(app, game_id, timestamp, options.unwrap_or_default())
+    }).await.map_err(|e| e.to_string())?
 }
 
 #[command]
-pub fn create_zip_for_cloud(game_name: String, timestamp: String) -> String {
-    BackupService::zip_for_cloud(game_name, timestamp)
+pub async fn restore_backup_dry_run(
+    app: AppHandle,
+    game_id: u32,
+    timestamp: String,
+    options: Option<RestoreOptions>
+) -> Result<RestorePlan, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        BackupService::plan_restore(app, game_id, timestamp, options.unwrap_or_default())
+    }).await.map_err(|e| e.to_string())?
+}
+
+#[command]
+pub async fn undo_last_restore(app: AppHandle, game_id: u32) -> Result<RestoreReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        BackupService::undo_last_restore(app, game_id)
+    }).await.map_err(|e| e.to_string())?
+}
+
+#[command]
+pub fn preview_retention(app: AppHandle, game_id: u32, policy: Option<RetentionPolicy>) -> Vec<RetentionDecision> {
+    let policy = policy.unwrap_or_else(|| ConfigService::load_config(&app).retention_policy());
+    BackupService::preview_retention(&app, game_id, &policy)
+}
+
+#[command]
+pub fn set_snapshot_pinned(app: AppHandle, game_id: u32, timestamp: String, pinned: bool) -> Result<(), String> {
+    BackupService::set_pinned(&app, game_id, timestamp, pinned)
+}
+
+#[command]
+pub fn annotate_snapshot(
+    app: AppHandle,
+    game_id: u32,
+    timestamp: String,
+    label: Option<String>,
+    note: Option<String>
+) -> Result<(), String> {
+    BackupService::annotate_snapshot(&app, game_id, timestamp, label, note)
+}
+
+#[command]
+pub async fn storage_report(app: AppHandle) -> Result<StorageReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        BackupService::storage_report(&app)
+    }).await.map_err(|e| e.to_string())
+}
+
+#[command]
+pub async fn create_zip_for_cloud(app: AppHandle, game_id: u32, timestamp: String) -> String {
+    tauri::async_runtime::spawn_blocking(move || {
+        BackupService::zip_for_cloud(&app, game_id, timestamp)
+    }).await.unwrap_or_else(|e| format!("Erro: {}", e))
+}
+
+#[command]
+pub async fn diff_backups(
+    app: AppHandle,
+    game_id: u32,
+    a: String,
+    b: String,
+    include_text: Option<bool>
+) -> Result<BackupDiff, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        BackupService::diff_backups(&app, game_id, a, b, include_text.unwrap_or(false))
+    }).await.map_err(|e| e.to_string())?
+}
+
+#[command]
+pub async fn verify_backup(app: AppHandle, game_id: u32, timestamp: String) -> Result<VerifyReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        BackupService::verify_backup(&app, game_id, timestamp)
+    }).await.map_err(|e| e.to_string())?
+}
+
+#[command]
+pub async fn verify_all_backups(window: Window) -> Result<Vec<VerifyReport>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        BackupService::verify_all_backups(window)
+    }).await.map_err(|e| e.to_string())
 }
 
 #[command]
 pub async fn toggle_auto_backup(
-    window: Window, 
     app: AppHandle, 
+    state: State<'_, WatcherState>,
+    sessions: State<'_, SessionState>,
     game_id: u32, 
     game_name: String, 
     enable: bool
 ) -> Result<String, String> {
+    let mut config = ConfigService::load_config(&app);
     if enable {
-        let mut paths = SteamService::get_manifest_paths(&app, game_id);
-        if let Some(cp) = SteamService::get_custom_path(&app, game_id) {
-            paths.push(cp);
-        }
-        if paths.is_empty() { return Err("Caminho não encontrado.".to_string()); }
-        WatcherService::start_watching(window, app, game_id, game_name, paths);
+        WatcherService::enable(&app, &state, &sessions, game_id, game_name.clone())?;
+        config.auto_backup_games.insert(game_id, game_name);
+        ConfigService::save_config(&app, config)?;
         Ok("Ativado".to_string())
     } else {
+        WatcherService::disable(&state, &sessions, game_id);
+        config.auto_backup_games.remove(&game_id);
+        ConfigService::save_config(&app, config)?;
         Ok("Desativado".to_string())
     }
 }
 
+#[command]
+pub fn save_watch_settings(
+    app: AppHandle,
+    state: State<'_, WatcherState>,
+    sessions: State<'_, SessionState>,
+    game_id: u32,
+    settings: WatchSettings
+) -> Result<(), String> {
+    let mut config = ConfigService::load_config(&app);
+    let previous = config.watch_settings(game_id).mode;
+    config.watch_settings.insert(game_id, WatchSettings {
+        quiet_secs: settings.quiet_secs.max(1),
+        max_delay_secs: settings.max_delay_secs.max(settings.quiet_secs.max(1)),
+        ..settings
+    });
+    let active = config.auto_backup_games.get(&game_id).cloned();
+    ConfigService::save_config(&app, config)?;
+
+    // Troca de modo com o auto-backup ligado reinicia no modo novo
+    match active {
+        Some(game_name) if previous != settings.mode => WatcherService::enable(&app, &state, &sessions, game_id, game_name),
+        _ => Ok(()),
+    }
+}
+
+#[command]
+pub fn get_watch_status(state: State<'_, WatcherState>, sessions: State<'_, SessionState>) -> Vec<WatchStatus> {
+    let mut status = WatcherService::list_watching(&state);
+    status.extend(SessionService::list_tracked(&sessions));
+    status.sort_by(|a, b| a.game_name.cmp(&b.game_name));
+    status
+}
+
+#[command]
+pub fn get_running_games(sessions: State<'_, SessionState>) -> Vec<GameSession> {
+    SessionService::running_games(&sessions)
+}
+
+#[command]
+pub fn get_unresolved_backup_folders(app: AppHandle) -> Vec<String> {
+    BackupService::unresolved_legacy_folders(&app)
+}
+
 #[command]
 pub fn load_app_config(app: AppHandle) -> AppConfig {
     ConfigService::load_config(&app)
 }
 
 #[command]
-pub fn save_app_config(app: AppHandle, retention_limit: usize) -> Result<(), String> {
-    ConfigService::save_config(&app, AppConfig { retention_limit })
+pub fn save_app_config(app: AppHandle, settings: AppConfigUpdate) -> Result<(), String> {
+    let mut config = ConfigService::load_config(&app);
+    if let Some(limit) = settings.retention_limit { config.retention_limit = limit; }
+    if let Some(hourly) = settings.retention_hourly { config.retention_hourly = hourly; }
+    if let Some(daily) = settings.retention_daily { config.retention_daily = daily; }
+    if let Some(weekly) = settings.retention_weekly { config.retention_weekly = weekly; }
+    // 0 desativa a cota
+    if let Some(mb) = settings.game_quota_mb { config.game_quota_mb = Some(mb); }
+    if let Some(mb) = settings.total_quota_mb { config.total_quota_mb = Some(mb); }
+    if let Some(excludes) = settings.default_excludes { config.default_excludes = excludes; }
+    if let Some(include) = settings.include_config_files { config.include_config_files = include; }
+    ConfigService::save_config(&app, config)
+}
+
+#[command]
+pub fn save_game_rules(app: AppHandle, game_id: u32, rules: FileRules) -> Result<(), String> {
+    let mut config = ConfigService::load_config(&app);
+    if rules == FileRules::default() {
+        config.game_rules.remove(&game_id);
+    } else {
+        config.game_rules.insert(game_id, rules);
+    }
+    ConfigService::save_config(&app, config)
+}
+
+#[command]
+pub async fn migrate_backup_root(window: Window, new_root: String) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        BackupService::migrate_backup_root(window, new_root)
+    }).await.map_err(|e| e.to_string())?
 }
 
 #[command]
@@ -4,6 +4,26 @@ mod commands;
 
 use std::sync::Mutex;
 use models::AuthState;
+use services::session_service::{SessionService, SessionState};
+use services::watcher_service::{WatcherService, WatcherState};
+use services::wrap_service::WrapService;
+use tauri::{Emitter, Manager, RunEvent};
+
+fn context() -> tauri::Context {
+    tauri::generate_context!()
+}
+
+/// `steam-save-manager wrap %command%`: roda o jogo entre dois backups sem
+/// abrir janela. Retorna o código de saída do jogo.
+pub fn wrap(command: Vec<String>) -> i32 {
+    let mut context = context();
+    context.config_mut().app.windows.clear();
+
+    let app = tauri::Builder::default()
+        .build(context)
+        .expect("error while building tauri application");
+    WrapService::run(app.handle().clone(), command)
+}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -12,17 +32,53 @@ pub fn run() {
         .manage(AuthState {
             access_token: Mutex::new(None),
         })
+        .manage(WatcherState::default())
+        .manage(SessionState::default())
+        .setup(|app| {
+            // Migração e auto-backup salvo rodam sem segurar a abertura da janela
+            let handle = app.handle().clone();
+            std::thread::spawn(move || {
+                // Backups antigos eram separados pelo nome do jogo; agora usam o app id
+                let folders = services::backup_service::BackupService::migrate_name_keyed_folders(&handle);
+                let _ = handle.emit("legacy-backups-unresolved", folders);
+
+                let unresolved = WatcherService::restore_saved(&handle, &handle.state::<WatcherState>(), &handle.state::<SessionState>());
+                for status in &unresolved {
+                    eprintln!("Auto-backup não reativado para {}: {}", status.game_name, status.error.as_deref().unwrap_or_default());
+                }
+                // Emitido sempre: a tela de configurações recarrega o status ao fim
+                let _ = handle.emit("auto-backup-restored", unresolved);
+            });
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // Comandos de Jogo
             commands::game_commands::get_installed_games,
+            commands::game_commands::get_steam_accounts,
             commands::game_commands::backup_game,
             commands::game_commands::update_manifest_db,
             commands::game_commands::get_backups,
+            commands::game_commands::browse_backup,
             commands::game_commands::restore_backup,
+            commands::game_commands::restore_backup_dry_run,
+            commands::game_commands::undo_last_restore,
+            commands::game_commands::preview_retention,
+            commands::game_commands::set_snapshot_pinned,
+            commands::game_commands::annotate_snapshot,
+            commands::game_commands::storage_report,
             commands::game_commands::create_zip_for_cloud,
+            commands::game_commands::diff_backups,
+            commands::game_commands::verify_backup,
+            commands::game_commands::verify_all_backups,
             commands::game_commands::toggle_auto_backup,
+            commands::game_commands::get_watch_status,
+            commands::game_commands::save_watch_settings,
+            commands::game_commands::get_running_games,
+            commands::game_commands::get_unresolved_backup_folders,
             commands::game_commands::load_app_config,
             commands::game_commands::save_app_config,
+            commands::game_commands::save_game_rules,
+            commands::game_commands::migrate_backup_root,
 
             // Comandos de Nuvem
             commands::cloud_commands::login_google_drive,
@@ -31,6 +87,12 @@ pub fn run() {
             commands::cloud_commands::get_google_user,
             commands::cloud_commands::logout_google
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(context())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                WatcherService::stop_all(&app.state::<WatcherState>());
+                SessionService::stop_all(&app.state::<SessionState>());
+            }
+        });
 }
\ No newline at end of file
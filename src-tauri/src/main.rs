@@ -2,5 +2,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("wrap") {
+        std::process::exit(steam_save_manager_lib::wrap(args.collect()));
+    }
     steam_save_manager_lib::run()
 }
@@ -20,6 +20,340 @@ pub struct BackupEntry {
     pub path: String,
     pub has_zip: bool,
     pub size_mb: String,
+    pub label: Option<String>,
+    pub note: Option<String>,
+    pub pinned: bool,
+    pub metadata: Option<SnapshotMetadata>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SnapshotFile {
+    pub path: String,
+    pub size: u64,
+    #[serde(default)]
+    pub mtime: i64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceKind {
+    Custom,
+    Manifest,
+    SteamCloud,
+}
+
+/// Pasta do snapshot (`Custom_Saves`, `Game_Data_<idx>`...) e o caminho de onde veio.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SnapshotSource {
+    pub folder: String,
+    pub kind: SourceKind,
+    pub origin: String,
+    pub pattern: Option<String>,
+    /// Caminho casado pelo curinga, relativo à parte fixa de `pattern`.
+    pub matched: Option<String>,
+    /// A origem é um arquivo isolado, e não uma pasta.
+    #[serde(default)]
+    pub is_file: bool,
+    /// SteamID3 da conta dona da pasta `userdata` (apenas Steam Cloud).
+    pub account_id: Option<u32>,
+    pub account_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SteamAccount {
+    pub id3: u32,
+    pub id64: u64,
+    pub account_name: Option<String>,
+    pub persona_name: Option<String>,
+    pub most_recent: bool,
+}
+
+impl SteamAccount {
+    pub fn display_name(&self) -> String {
+        self.persona_name.clone()
+            .or_else(|| self.account_name.clone())
+            .unwrap_or_else(|| self.id3.to_string())
+    }
+}
+
+/// Opções de restauração:
+/// - `source_account`/`target_account`: restaura o Steam Cloud de uma conta
+///   (ou de todas as contas do snapshot) na pasta `userdata` de outra;
+/// - `paths`: restaura só esses arquivos/pastas do snapshot;
+/// - `destination`: grava em outra pasta em vez do local original.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct RestoreOptions {
+    pub source_account: Option<u32>,
+    pub target_account: Option<u32>,
+    pub paths: Option<Vec<String>>,
+    pub destination: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SnapshotTreeNode {
+    pub name: String,
+    pub path: String,
+    pub is_dir: bool,
+    pub size: u64,
+    pub mtime: i64,
+    pub children: Vec<SnapshotTreeNode>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotKind {
+    #[default]
+    Backup,
+    /// Estado dos saves capturado automaticamente antes de uma restauração.
+    PreRestore,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct SnapshotMetadata {
+    pub kind: SnapshotKind,
+    pub restored_from: Option<String>,
+    pub label: Option<String>,
+    pub note: Option<String>,
+    /// Snapshots fixados nunca são removidos pela retenção.
+    pub pinned: bool,
+    pub app_id: u32,
+    pub game_name: String,
+    pub app_version: Option<String>,
+    pub tool_version: String,
+    pub created_at: String,
+    pub sources: Vec<SnapshotSource>,
+    /// Regras de inclusão/exclusão efetivamente aplicadas neste backup.
+    pub rules: FileRules,
+    pub files: Vec<SnapshotFile>,
+}
+
+/// Padrões glob relativos a cada pasta de origem (`*.log`, `Crashes/`, `saves/*.sav`).
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct FileRules {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RestoreTargetError {
+    pub folder: String,
+    pub target: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RestoreReport {
+    pub success: bool,
+    pub restored_files: usize,
+    pub targets: Vec<String>,
+    pub errors: Vec<RestoreTargetError>,
+    pub rolled_back: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlannedAction {
+    Create,
+    Overwrite,
+    Unchanged,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlannedFile {
+    pub path: String,
+    pub target: String,
+    pub action: PlannedAction,
+    pub old_size: Option<u64>,
+    pub old_mtime: Option<i64>,
+    pub new_size: u64,
+    pub new_mtime: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RestoreTargetPlan {
+    pub folder: String,
+    pub target: String,
+    pub exists: bool,
+    pub files: Vec<PlannedFile>,
+}
+
+/// Resultado de uma restauração em modo dry-run: nada é gravado em disco.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RestorePlan {
+    pub snapshot: String,
+    pub targets: Vec<RestoreTargetPlan>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChange {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileDiff {
+    pub path: String,
+    pub change: FileChange,
+    pub old_size: Option<u64>,
+    pub new_size: Option<u64>,
+    pub size_delta: i64,
+    pub unified_diff: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupDiff {
+    pub files: Vec<FileDiff>,
+    pub total_size_delta: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct IntegrityIssues {
+    pub missing: Vec<String>,
+    pub modified: Vec<String>,
+    pub extra: Vec<String>,
+}
+
+impl IntegrityIssues {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.modified.is_empty() && self.extra.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VerifyReport {
+    pub game: String,
+    pub snapshot: String,
+    pub files_checked: usize,
+    pub store: IntegrityIssues,
+    pub zip: Option<IntegrityIssues>,
+    pub ok: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct VerifyProgress {
+    pub current: usize,
+    pub total: usize,
+    pub game: String,
+    pub snapshot: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MigrationProgress {
+    pub current: usize,
+    pub total: usize,
+    pub path: String,
+}
+
+/// Política avô-pai-filho: além dos `keep_last` mais recentes, mantém o
+/// snapshot mais novo de cada uma das últimas `hourly` horas, `daily` dias
+/// e `weekly` semanas que têm backup.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    pub keep_last: usize,
+    pub hourly: usize,
+    pub daily: usize,
+    pub weekly: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionReason {
+    Last,
+    Hourly,
+    Daily,
+    Weekly,
+    Pinned,
+    PreRestore,
+    /// `snapshot.json` existe mas não pôde ser lido.
+    Unreadable,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RetentionDecision {
+    pub name: String,
+    pub keep: bool,
+    /// Vazio quando o snapshot seria removido.
+    pub reasons: Vec<RetentionReason>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GameStorage {
+    pub app_id: u32,
+    pub game_name: String,
+    pub snapshots: usize,
+    /// Bytes dos blobs usados pelo jogo (blobs compartilhados contam uma vez).
+    pub size: u64,
+    pub reclaimable: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StorageReport {
+    pub games: Vec<GameStorage>,
+    /// Bytes referenciados por algum snapshot.
+    pub total_size: u64,
+    /// Bytes ocupados no disco pelo store, incluindo blobs órfãos.
+    pub store_size: u64,
+    pub per_game_quota: Option<u64>,
+    pub total_quota: Option<u64>,
+    pub reclaimable: u64,
+    /// Snapshots (`<jogo>/<timestamp>`) que as cotas removeriam.
+    pub to_delete: Vec<String>,
+}
+
+/// Quando o auto-backup copia os saves.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoBackupMode {
+    /// Após uma rajada de mudanças nos arquivos de save.
+    #[default]
+    Files,
+    /// Logo depois que o jogo fecha, sem risco de copiar arquivos pela metade.
+    Session,
+}
+
+/// Tempo de espera do auto-backup depois que o jogo para de gravar.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(default)]
+pub struct WatchSettings {
+    pub mode: AutoBackupMode,
+    /// Segundos sem nenhuma mudança antes de fazer o backup.
+    pub quiet_secs: u64,
+    /// Limite desde a primeira mudança, para jogos que gravam sem parar.
+    pub max_delay_secs: u64,
+    /// Espera após o jogo fechar, no modo `Session`.
+    pub exit_delay_secs: u64,
+}
+
+impl Default for WatchSettings {
+    fn default() -> Self {
+        Self { mode: AutoBackupMode::Files, quiet_secs: 5, max_delay_secs: 60, exit_delay_secs: 10 }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WatchStatus {
+    pub game_id: u32,
+    pub game_name: String,
+    pub mode: AutoBackupMode,
+    pub paths: Vec<String>,
+    /// `false` se a thread do watcher terminou inesperadamente.
+    pub running: bool,
+    /// Motivo quando o watcher salvo não pôde ser iniciado.
+    pub error: Option<String>,
+}
+
+/// Payload de `game-started`/`game-stopped`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GameSession {
+    pub game_id: u32,
+    pub game_name: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -37,7 +371,38 @@ pub struct LudusaviManifest {
 #[derive(Debug, Deserialize)]
 pub struct LudusaviGame {
     pub steam: Option<LudusaviSteam>,
-    pub files: Option<HashMap<String, serde_yaml::Value>>,
+    pub files: Option<HashMap<String, Option<LudusaviFile>>>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct LudusaviFile {
+    /// `save` e/ou `config`.
+    pub tags: Vec<String>,
+    /// O caminho vale se qualquer uma das condições bater (vazio = sempre).
+    pub when: Vec<LudusaviWhen>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct LudusaviWhen {
+    /// `windows`, `linux`, `mac`...
+    pub os: Option<String>,
+    /// `steam`, `gog`, `epic`...
+    pub store: Option<String>,
+}
+
+impl LudusaviFile {
+    /// `os` é o sistema em que o jogo roda (`windows` para jogos no Proton).
+    pub fn applies_to(&self, os: &str, store: &str) -> bool {
+        self.when.is_empty() || self.when.iter().any(|w| {
+            w.os.as_deref().is_none_or(|o| o == os) && w.store.as_deref().is_none_or(|s| s == store)
+        })
+    }
+
+    pub fn is_config_only(&self) -> bool {
+        self.tags.iter().any(|t| t == "config") && !self.tags.iter().any(|t| t == "save")
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -48,4 +413,8 @@ pub struct LudusaviSteam {
 #[derive(Debug, Deserialize)]
 pub struct CustomGameEntry {
     pub win: String,
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
 }
\ No newline at end of file
@@ -1,210 +1,985 @@
 // src-tauri/src/services/backup_service.rs
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
-use std::io::{Read, Write};
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
 use chrono::Local;
 use directories::UserDirs;
-use steamlocate::SteamDir;
-use tauri::{AppHandle, Window, Emitter}; // ESSENCIAL: Permite usar window.emit()
+use tauri::{AppHandle, Window, Emitter, Manager}; // ESSENCIAL: Permite usar emit()
 use zip::write::SimpleFileOptions;
-use crate::models::BackupEntry;
+use sha2::{Digest, Sha256};
+use crate::models::{
+    BackupDiff, BackupEntry, FileRules, IntegrityIssues, MigrationProgress, PlannedAction, PlannedFile,
+    RestoreOptions, RestorePlan, RestoreReport, RestoreTargetError, RestoreTargetPlan, RetentionDecision,
+    RetentionPolicy, RetentionReason, SnapshotFile, SnapshotKind, SnapshotMetadata, SnapshotSource,
+    SnapshotTreeNode, SourceKind, StorageReport, VerifyProgress, VerifyReport,
+};
+use crate::services::config_service::ConfigService;
+use crate::services::diff_service::DiffService;
+use crate::services::file_filter::FileFilter;
+use crate::services::restore_transaction::RestoreTransaction;
+use crate::services::retention_service::RetentionService;
+use crate::services::snapshot_store::{SnapshotStore, StoreGuard, LOCK_FILE, METADATA_FILE, STORE_DIR};
 use crate::services::steam_service::SteamService;
+use crate::services::storage_service::StorageService;
+
+/// Snapshots de segurança mantidos fora da contagem normal de retenção.
+const PRE_RESTORE_KEEP: usize = 3;
+const PRE_RESTORE_SUFFIX: &str = "_pre-restore";
+const BACKUPS_DIR: &str = "SaveManagerBackups";
 
 pub struct BackupService;
 
 impl BackupService {
-    pub fn perform_backup(window: Window, app: AppHandle, game_id: u32, game_name: String) -> String {
+    pub fn default_backups_root() -> PathBuf {
         let user_dirs = UserDirs::new().unwrap();
         let doc_dir = user_dirs.document_dir().unwrap_or_else(|| user_dirs.home_dir());
-        let timestamp = Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
-        let safe_name = game_name.replace(|c: char| !c.is_alphanumeric() && c != ' ', "_");
-        let backup_root = doc_dir.join("SaveManagerBackups").join(&safe_name).join(&timestamp);
+        doc_dir.join(BACKUPS_DIR)
+    }
 
-        // Notifica o frontend sobre o início do processo
-        let _ = window.emit("backup-status", format!("Iniciando backup de {}...", game_name));
+    /// Pasta raiz dos backups: `<backup_root>/SaveManagerBackups` ou
+    /// `Documents/SaveManagerBackups`. A subpasta é só do app, mesmo que
+    /// `backup_root` aponte para um disco com outros arquivos.
+    pub fn backups_root(app: &AppHandle) -> PathBuf {
+        ConfigService::load_config(app).backup_root
+            .filter(|p| !p.trim().is_empty())
+            .map(|p| PathBuf::from(p).join(BACKUPS_DIR))
+            .unwrap_or_else(Self::default_backups_root)
+    }
+
+    /// Trava o store da raiz atual. Se uma migração trocou a raiz enquanto
+    /// esperávamos, trava de novo na raiz nova.
+    fn lock_root(app: &AppHandle) -> Result<(StoreGuard, PathBuf), String> {
+        loop {
+            let root = Self::backups_root(app);
+            let guard = SnapshotStore::lock(&root)?;
+            if Self::backups_root(app) == root {
+                return Ok((guard, root));
+            }
+        }
+    }
+
+    /// Backups ficam em `<raiz>/<app id>`; o nome do jogo vai no snapshot.json.
+    pub fn game_dir(app: &AppHandle, game_id: u32) -> PathBuf {
+        Self::backups_root(app).join(game_id.to_string())
+    }
+
+    /// Migra as pastas antigas nomeadas por `safe_name` para pastas por app id.
+    /// O app id vem do snapshot.json ou, para backups sem metadados, do nome
+    /// de um jogo instalado. Retorna as pastas que não puderam ser associadas.
+    pub fn migrate_name_keyed_folders(app: &AppHandle) -> Vec<String> {
+        let root = Self::backups_root(app);
+        let legacy = Self::name_keyed_dirs(&root);
+        if legacy.is_empty() { return Vec::new(); }
+
+        let installed = SteamService::list_installed_games(app);
+        let mut unresolved = Vec::new();
+        let _guard = match SnapshotStore::lock(&root) {
+            Ok(guard) => guard,
+            Err(_) => return legacy.iter().map(|dir| dir.file_name().unwrap().to_string_lossy().to_string()).collect(),
+        };
+
+        for dir in legacy {
+            let folder = dir.file_name().unwrap().to_string_lossy().to_string();
+            let game_id = SnapshotStore::snapshot_dirs(&dir)
+                .iter()
+                .filter_map(|s| SnapshotStore::read_metadata(s))
+                .map(|m| m.app_id)
+                .find(|id| *id != 0)
+                .or_else(|| installed.iter().find(|g| Self::safe_name(&g.name) == folder).map(|g| g.id));
+
+            let game_id = match game_id {
+                Some(id) => id,
+                None => {
+                    unresolved.push(folder);
+                    continue;
+                }
+            };
+
+            let target = root.join(game_id.to_string());
+            if !target.exists() {
+                if fs::rename(&dir, &target).is_err() { unresolved.push(folder); }
+                continue;
+            }
+
+            // Já existe uma pasta com o app id: move snapshot por snapshot
+            let mut complete = true;
+            for entry in fs::read_dir(&dir).map(|e| e.flatten().collect::<Vec<_>>()).unwrap_or_default() {
+                let moved = target.join(entry.file_name());
+                if moved.exists() || fs::rename(entry.path(), &moved).is_err() {
+                    complete = false;
+                }
+            }
+            if complete {
+                let _ = fs::remove_dir(&dir);
+            } else {
+                unresolved.push(folder);
+            }
+        }
+        unresolved
+    }
+
+    /// Pastas ainda nomeadas pelo jogo; depois da migração, só as sem app id.
+    pub fn unresolved_legacy_folders(app: &AppHandle) -> Vec<String> {
+        Self::name_keyed_dirs(&Self::backups_root(app))
+            .iter()
+            .map(|dir| dir.file_name().unwrap().to_string_lossy().to_string())
+            .collect()
+    }
+
+    fn name_keyed_dirs(root: &Path) -> Vec<PathBuf> {
+        SnapshotStore::game_dirs(root)
+            .into_iter()
+            .filter(|dir| dir.file_name().is_some_and(|n| n.to_string_lossy().parse::<u32>().is_err()))
+            .collect()
+    }
 
-        if let Err(e) = fs::create_dir_all(&backup_root) {
-            return format!("Erro IO: {}", e);
+    fn safe_name(game_name: &str) -> String {
+        game_name.replace(|c: char| !c.is_alphanumeric() && c != ' ', "_")
+    }
+
+    /// Move todos os backups para `<new_root>/SaveManagerBackups`, que precisa estar
+    /// vazia ou não existir. Cada arquivo é copiado e conferido (tamanho e SHA-256)
+    /// antes de a pasta antiga ser apagada; se algo falhar, a cópia parcial é
+    /// removida e a pasta antiga e a configuração ficam intactas.
+    pub fn migrate_backup_root(window: Window, new_root: String) -> Result<String, String> {
+        let app = window.app_handle();
+        let old_root = Self::backups_root(app);
+        let new_parent = PathBuf::from(new_root.trim());
+        if new_parent.as_os_str().is_empty() { return Err("Destino inválido.".to_string()); }
+        let new_root = new_parent.join(BACKUPS_DIR);
+        if new_root == old_root { return Ok("Nada a migrar".to_string()); }
+        if new_root.starts_with(&old_root) || old_root.starts_with(&new_root) {
+            return Err("O novo destino não pode estar dentro da pasta atual (nem o contrário).".to_string());
+        }
+        // Mesclar com backups de outra instalação misturaria os stores
+        if fs::read_dir(&new_root).is_ok_and(|mut entries| entries.next().is_some()) {
+            return Err(format!("{} já existe e não está vazia.", new_root.to_string_lossy()));
+        }
+
+        // Nenhum backup ou GC pode mexer na pasta antiga durante a cópia
+        let guard = SnapshotStore::lock(&old_root)?;
+
+        let copied = Self::copy_verified(&window, &old_root, &new_root).and_then(|total| {
+            let mut config = ConfigService::load_config(app);
+            config.backup_root = Some(new_parent.to_string_lossy().to_string());
+            ConfigService::save_config(app, config).map(|_| total)
+        });
+        let total = match copied {
+            Ok(total) => total,
+            Err(e) => {
+                let _ = fs::remove_dir_all(&new_root);
+                return Err(e);
+            }
+        };
+
+        // No Windows o `.store/lock` aberto impediria a remoção
+        drop(guard);
+        if old_root.exists() {
+            let _ = fs::remove_dir_all(&old_root);
         }
+        Ok(format!("{} arquivos migrados", total))
+    }
+
+    fn copy_verified(window: &Window, old_root: &Path, new_root: &Path) -> Result<usize, String> {
+        let mut files = SnapshotStore::relative_files(old_root).map_err(|e| e.to_string())?;
+        let lock_file = Path::new(STORE_DIR).join(LOCK_FILE);
+        files.retain(|rel| *rel != lock_file);
+
+        let total = files.len();
+        for (idx, rel) in files.iter().enumerate() {
+            let source = old_root.join(rel);
+            let target = new_root.join(rel);
+            let _ = window.emit("migrate-progress", MigrationProgress {
+                current: idx + 1,
+                total,
+                path: rel.to_string_lossy().to_string(),
+            });
+
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            fs::copy(&source, &target).map_err(|e| format!("{}: {}", rel.to_string_lossy(), e))?;
+
+            let same_size = fs::metadata(&source).map(|m| m.len()).ok() == fs::metadata(&target).map(|m| m.len()).ok();
+            let same_hash = same_size && SnapshotStore::hash_file(&source).ok() == SnapshotStore::hash_file(&target).ok();
+            if !same_hash {
+                return Err(format!("Verificação falhou: {}", rel.to_string_lossy()));
+            }
+        }
+        Ok(total)
+    }
+
+    pub fn perform_backup(app: AppHandle, game_id: u32, game_name: String, label: Option<String>) -> String {
+        match Self::create_snapshot(&app, game_id, &game_name, SnapshotKind::Backup, label) {
+            Ok(Some(timestamp)) => format!("Sucesso:{}", timestamp),
+            Ok(None) => "Erro: Nenhum arquivo localizado.".to_string(),
+            Err(e) => e,
+        }
+    }
+
+    /// Backup automático (watcher, sessão) seguido da política de retenção salva.
+    pub fn perform_auto_backup(app: &AppHandle, game_id: u32, game_name: &str, label: Option<String>) -> String {
+        let result = Self::perform_backup(app.clone(), game_id, game_name.to_string(), label);
+        Self::apply_retention(app, game_id);
+        result
+    }
+
+    /// Limpa os backups do jogo com a política de retenção salva.
+    pub fn apply_retention(app: &AppHandle, game_id: u32) {
+        let policy = ConfigService::load_config(app).retention_policy();
+        let _ = Self::cleanup_old_backups(app, game_id, &policy);
+    }
+
+    /// Captura o estado atual dos saves. Retorna o nome do snapshot criado,
+    /// ou `None` se nenhum arquivo foi localizado.
+    fn create_snapshot(app: &AppHandle, game_id: u32, game_name: &str, kind: SnapshotKind, label: Option<String>) -> Result<Option<String>, String> {
+        // Notifica o frontend sobre o início do processo
+        let _ = app.emit("backup-status", format!("Iniciando backup de {}...", game_name));
+
+        let rules = Self::file_rules(app, game_id);
+        let filter = FileFilter::new(&rules);
 
+        // A raiz só é lida depois da trava: uma migração em andamento pode trocá-la
+        let (_guard, root) = Self::lock_root(app)?;
+        let mut timestamp = Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
+        if kind == SnapshotKind::PreRestore {
+            timestamp.push_str(PRE_RESTORE_SUFFIX);
+        }
+        let snapshot_dir = root.join(game_id.to_string()).join(&timestamp);
+        let mut metadata = SnapshotMetadata {
+            kind,
+            label: Self::clean_text(label),
+            app_id: game_id,
+            game_name: game_name.to_string(),
+            app_version: SteamService::get_app_build_id(game_id).map(|b| b.to_string()),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: Local::now().to_rfc3339(),
+            rules,
+            ..Default::default()
+        };
         let mut count = 0;
-        let options = fs_extra::dir::CopyOptions::new().overwrite(true).copy_inside(true);
 
         // 1. Saves Customizados
-        if let Some(path) = SteamService::get_custom_path(&app, game_id) {
+        if let Some(path) = SteamService::get_custom_path(app, game_id) {
             if path.exists() {
-                let _ = window.emit("backup-status", "Copiando saves manuais...");
-                let _ = fs::create_dir_all(backup_root.join("Custom_Saves"));
-                if fs_extra::dir::copy(&path, backup_root.join("Custom_Saves"), &options).is_ok() {
+                let _ = app.emit("backup-status", "Copiando saves manuais...");
+                if let Ok(files) = SnapshotStore::put_dir(&root, &path, "Custom_Saves", &filter) {
+                    SnapshotStore::merge_files(&mut metadata.files, files);
+                    metadata.sources.push(SnapshotSource {
+                        folder: "Custom_Saves".to_string(),
+                        kind: SourceKind::Custom,
+                        origin: path.to_string_lossy().to_string(),
+                        pattern: None,
+                        matched: None,
+                        is_file: false,
+                        account_id: None,
+                        account_name: None,
+                    });
                     count += 1;
                 }
             }
         }
 
         // 2. Saves via Manifesto (Ludusavi)
-        let manifest_paths = SteamService::get_manifest_paths(&app, game_id);
-        if !manifest_paths.is_empty() {
-            let _ = window.emit("backup-status", "Sincronizando via Manifesto...");
-            for (idx, path) in manifest_paths.iter().enumerate() {
-                if path.exists() {
-                    let _ = fs::create_dir_all(backup_root.join(format!("Game_Data_{}", idx)));
-                    if fs_extra::dir::copy(path, backup_root.join(format!("Game_Data_{}", idx)), &options).is_ok() {
+        // Cada arquivo ou pasta casado por um curinga vira uma origem própria
+        let manifest_entries = SteamService::get_manifest_entries(app, game_id);
+        if !manifest_entries.is_empty() {
+            let _ = app.emit("backup-status", "Sincronizando via Manifesto...");
+            let ctx = SteamService::placeholder_context(game_id);
+            let mut idx = 0;
+            for (pattern, root_path) in &manifest_entries {
+                for path in SteamService::expand_manifest_path(pattern, &ctx) {
+                    let folder = format!("Game_Data_{}", idx);
+                    idx += 1;
+                    if let Ok(files) = SnapshotStore::put_path(&root, &path, &folder, &filter) {
+                        SnapshotStore::merge_files(&mut metadata.files, files);
+                        metadata.sources.push(SnapshotSource {
+                            folder,
+                            kind: SourceKind::Manifest,
+                            origin: path.to_string_lossy().to_string(),
+                            pattern: Some(pattern.clone()),
+                            matched: path.strip_prefix(root_path).ok()
+                                .filter(|rel| !rel.as_os_str().is_empty())
+                                .map(|rel| rel.to_string_lossy().replace('\\', "/")),
+                            is_file: path.is_file(),
+                            account_id: None,
+                            account_name: None,
+                        });
                         count += 1;
                     }
                 }
             }
         }
 
-        // 3. Saves Steam Cloud Local
-        if let Ok(steamdir) = SteamDir::locate() {
-            let _ = window.emit("backup-status", "Verificando Steam Cloud local...");
-            if let Ok(entries) = fs::read_dir(steamdir.path().join("userdata")) {
-                for entry in entries.flatten() {
-                    let possible = entry.path().join(game_id.to_string());
-                    if possible.exists() {
-                        let _ = fs::create_dir_all(backup_root.join("Steam_Cloud"));
-                        if fs_extra::dir::copy(&possible, backup_root.join("Steam_Cloud"), &options).is_ok() {
-                            count += 1;
-                        }
-                    }
-                }
+        // 3. Saves Steam Cloud Local (uma subpasta por conta)
+        let _ = app.emit("backup-status", "Verificando Steam Cloud local...");
+        let accounts = SteamService::get_steam_accounts();
+        for (account_id, possible) in SteamService::get_userdata_app_dirs(game_id) {
+            let folder = format!("Steam_Cloud/{}", account_id);
+            if let Ok(files) = SnapshotStore::put_dir(&root, &possible, &folder, &filter) {
+                SnapshotStore::merge_files(&mut metadata.files, files);
+                metadata.sources.push(SnapshotSource {
+                    folder,
+                    kind: SourceKind::SteamCloud,
+                    origin: possible.to_string_lossy().to_string(),
+                    pattern: None,
+                    matched: None,
+                    is_file: false,
+                    account_id: Some(account_id),
+                    account_name: accounts.iter().find(|a| a.id3 == account_id).map(|a| a.display_name()),
+                });
+                count += 1;
             }
         }
 
         if count > 0 {
-            let _ = window.emit("backup-status", "Backup concluído!");
-            format!("Sucesso:{}", timestamp)
+            if let Err(e) = SnapshotStore::write_metadata(&snapshot_dir, &metadata) {
+                return Err(format!("Erro IO: {}", e));
+            }
+            let _ = app.emit("backup-status", "Backup concluído!");
+            Ok(Some(timestamp))
         } else {
-            let _ = fs::remove_dir_all(&backup_root);
-            "Erro: Nenhum arquivo localizado.".to_string()
+            Ok(None)
         }
     }
 
-    pub fn cleanup_old_backups(game_name: String, limit: usize) -> Result<usize, String> {
-        let user_dirs = UserDirs::new().unwrap();
-        let doc_dir = user_dirs.document_dir().unwrap_or_else(|| user_dirs.home_dir());
-        let safe_name = game_name.replace(|c: char| !c.is_alphanumeric() && c != ' ', "_");
-        let backup_root = doc_dir.join("SaveManagerBackups").join(&safe_name);
-
-        if !backup_root.exists() { return Ok(0); }
-
-        let mut entries: Vec<_> = fs::read_dir(&backup_root)
-            .map_err(|e| e.to_string())?
-            .filter_map(|res| res.ok())
-            .filter(|e| e.path().is_dir())
-            .collect();
+    /// Exclusões globais + regras do jogo na configuração + regras do `custom_manifest.json`.
+    fn file_rules(app: &AppHandle, game_id: u32) -> FileRules {
+        let mut config = ConfigService::load_config(app);
+        let mut rules = FileRules {
+            include: Vec::new(),
+            exclude: std::mem::take(&mut config.default_excludes),
+        };
+        if let Some(game) = config.game_rules.remove(&game_id) {
+            rules.include.extend(game.include);
+            rules.exclude.extend(game.exclude);
+        }
+        if let Some(custom) = SteamService::get_custom_entry(app, game_id) {
+            rules.include.extend(custom.include);
+            rules.exclude.extend(custom.exclude);
+        }
+        rules
+    }
 
-        entries.sort_by_key(|e| e.file_name());
+    /// Remove os snapshots que a política não mantém. Snapshots fixados e
+    /// os de segurança (que têm cota própria) não entram na política.
+    pub fn cleanup_old_backups(app: &AppHandle, game_id: u32, policy: &RetentionPolicy) -> Result<usize, String> {
+        let game_dir = Self::game_dir(app, game_id);
+        if !game_dir.exists() { return Ok(0); }
 
         let mut deleted = 0;
-        if entries.len() > limit {
-            let to_delete = entries.len() - limit;
-            for i in 0..to_delete {
-                if fs::remove_dir_all(entries[i].path()).is_ok() {
-                    deleted += 1;
-                }
+        for decision in Self::retention_plan(&game_dir, policy) {
+            if !decision.keep && fs::remove_dir_all(game_dir.join(&decision.name)).is_ok() {
+                deleted += 1;
             }
         }
+        deleted += Self::enforce_quotas(app);
+
+        if deleted > 0 {
+            SnapshotStore::collect_garbage(&Self::backups_root(app))?;
+        }
         Ok(deleted)
     }
 
-    pub fn list_backups(game_name: String) -> Vec<BackupEntry> {
-        let user_dirs = UserDirs::new().unwrap();
-        let doc_dir = user_dirs.document_dir().unwrap_or_else(|| user_dirs.home_dir());
-        let safe_name = game_name.replace(|c: char| !c.is_alphanumeric() && c != ' ', "_");
-        let backup_root = doc_dir.join("SaveManagerBackups").join(safe_name);
-        
-        let mut backups = Vec::new();
-        if !backup_root.exists() { return backups; }
-        
-        if let Ok(entries) = fs::read_dir(&backup_root) {
-            for entry in entries.flatten() {
-                if let Ok(ft) = entry.file_type() {
-                    if ft.is_dir() {
-                        let name = entry.file_name().to_string_lossy().to_string();
-                        let zip_path = backup_root.join(format!("{}.zip", name));
-                        let size = fs_extra::dir::get_size(entry.path()).unwrap_or(0);
-                        backups.push(BackupEntry {
-                            name,
-                            path: entry.path().to_string_lossy().to_string(),
-                            has_zip: zip_path.exists(),
-                            size_mb: format!("{:.1} MB", size as f64 / 1024.0 / 1024.0),
-                        });
-                    }
+    /// Remove os snapshots mais antigos (não fixados) até respeitar as cotas
+    /// por jogo e global. O GC fica a cargo de quem chama.
+    fn enforce_quotas(app: &AppHandle) -> usize {
+        let quota = ConfigService::load_config(app).storage_quota();
+        if quota.per_game_mb.is_none() && quota.total_mb.is_none() { return 0; }
+
+        StorageService::plan(&Self::backups_root(app), quota)
+            .deletions
+            .iter()
+            .filter(|dir| fs::remove_dir_all(dir).is_ok())
+            .count()
+    }
+
+    pub fn storage_report(app: &AppHandle) -> StorageReport {
+        let quota = ConfigService::load_config(app).storage_quota();
+        StorageService::report(&Self::backups_root(app), quota)
+    }
+
+    /// Mostra o que `cleanup_old_backups` faria, sem apagar nada.
+    pub fn preview_retention(app: &AppHandle, game_id: u32, policy: &RetentionPolicy) -> Vec<RetentionDecision> {
+        Self::retention_plan(&Self::game_dir(app, game_id), policy)
+    }
+
+    fn retention_plan(game_dir: &Path, policy: &RetentionPolicy) -> Vec<RetentionDecision> {
+        let mut decisions = Vec::new();
+        let mut regular = Vec::new();
+        let mut safety = Vec::new();
+        for dir in SnapshotStore::snapshot_dirs(game_dir) {
+            let name = dir.file_name().unwrap().to_string_lossy().to_string();
+            // Sem `snapshot.json` é um snapshot antigo; com ele ilegível, não arrisca apagar
+            let metadata = match SnapshotStore::read_metadata(&dir) {
+                Some(metadata) => metadata,
+                None if dir.join(METADATA_FILE).exists() => {
+                    decisions.push(RetentionDecision { name, keep: true, reasons: vec![RetentionReason::Unreadable] });
+                    continue;
                 }
+                None => SnapshotMetadata::default(),
+            };
+            if metadata.pinned {
+                decisions.push(RetentionDecision { name, keep: true, reasons: vec![RetentionReason::Pinned] });
+            } else if metadata.kind == SnapshotKind::PreRestore {
+                safety.push(name);
+            } else {
+                regular.push(name);
             }
         }
+
+        decisions.extend(RetentionService::apply(&regular, policy));
+
+        safety.sort_by(|a, b| b.cmp(a));
+        for (idx, name) in safety.into_iter().enumerate() {
+            let keep = idx < PRE_RESTORE_KEEP;
+            let reasons = if keep { vec![RetentionReason::PreRestore] } else { Vec::new() };
+            decisions.push(RetentionDecision { name, keep, reasons });
+        }
+
+        decisions.sort_by(|a, b| b.name.cmp(&a.name));
+        decisions
+    }
+
+    pub fn set_pinned(app: &AppHandle, game_id: u32, timestamp: String, pinned: bool) -> Result<(), String> {
+        Self::edit_metadata(app, game_id, &timestamp, |metadata| metadata.pinned = pinned)
+    }
+
+    pub fn annotate_snapshot(app: &AppHandle, game_id: u32, timestamp: String, label: Option<String>, note: Option<String>) -> Result<(), String> {
+        Self::edit_metadata(app, game_id, &timestamp, |metadata| {
+            metadata.label = Self::clean_text(label);
+            metadata.note = Self::clean_text(note);
+        })
+    }
+
+    fn edit_metadata(app: &AppHandle, game_id: u32, timestamp: &str, edit: impl FnOnce(&mut SnapshotMetadata)) -> Result<(), String> {
+        let root = Self::backups_root(app);
+        let snapshot_dir = Self::game_dir(app, game_id).join(timestamp);
+        if !snapshot_dir.exists() { return Err("Backup não encontrado.".to_string()); }
+
+        let mut metadata = SnapshotStore::load_or_import(&root, &snapshot_dir)?;
+        edit(&mut metadata);
+        SnapshotStore::write_metadata(&snapshot_dir, &metadata)
+    }
+
+    /// Texto em branco vira `None`.
+    fn clean_text(text: Option<String>) -> Option<String> {
+        text.map(|t| t.trim().to_string()).filter(|t| !t.is_empty())
+    }
+
+    pub fn list_backups(app: &AppHandle, game_id: u32) -> Vec<BackupEntry> {
+        let root = Self::backups_root(app);
+        let game_dir = Self::game_dir(app, game_id);
+
+        let mut backups = Vec::new();
+        if !game_dir.exists() { return backups; }
+
+        for snapshot_dir in SnapshotStore::snapshot_dirs(&game_dir) {
+            let metadata = match SnapshotStore::load_or_import(&root, &snapshot_dir) {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+            let name = snapshot_dir.file_name().unwrap().to_string_lossy().to_string();
+            let zip_path = game_dir.join(format!("{}.zip", name));
+            let size: u64 = metadata.files.iter().map(|f| f.size).sum();
+            backups.push(BackupEntry {
+                name,
+                path: snapshot_dir.to_string_lossy().to_string(),
+                has_zip: zip_path.exists(),
+                size_mb: format!("{:.1} MB", size as f64 / 1024.0 / 1024.0),
+                label: metadata.label.clone(),
+                note: metadata.note.clone(),
+                pinned: metadata.pinned,
+                metadata: Some(metadata),
+            });
+        }
         backups.sort_by(|a, b| b.name.cmp(&a.name));
         backups
     }
 
-    pub fn restore_backup(app: AppHandle, game_id: u32, game_name: String, timestamp: String) -> String {
-        let user_dirs = UserDirs::new().unwrap();
-        let doc_dir = user_dirs.document_dir().unwrap_or_else(|| user_dirs.home_dir());
-        let safe_name = game_name.replace(|c: char| !c.is_alphanumeric() && c != ' ', "_");
-        let backup_root = doc_dir.join("SaveManagerBackups").join(&safe_name).join(&timestamp);
+    fn snapshot_kind(snapshot_dir: &Path) -> SnapshotKind {
+        SnapshotStore::read_metadata(snapshot_dir).map(|m| m.kind).unwrap_or_default()
+    }
+
+    pub fn restore_backup(app: AppHandle, game_id: u32, timestamp: String, options: RestoreOptions) -> Result<RestoreReport, String> {
+        let snapshot_dir = Self::game_dir(&app, game_id).join(&timestamp);
+        if !snapshot_dir.exists() { return Err("Backup não encontrado.".to_string()); }
+        let metadata = SnapshotStore::read_metadata(&snapshot_dir);
+        if let Some(metadata) = &metadata {
+            Self::check_accounts(metadata, &options)?;
+        }
+        let game_name = metadata
+            .map(|m| m.game_name)
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| game_id.to_string());
+
+        // Guarda o estado atual antes de sobrescrever, para permitir desfazer.
+        // Restaurar para outra pasta não toca nos saves, então não precisa.
+        let safety = if options.destination.is_none() {
+            Self::create_snapshot(&app, game_id, &game_name, SnapshotKind::PreRestore, None)
+        } else {
+            Ok(None)
+        };
+        match safety {
+            Ok(Some(safety)) => {
+                let safety_dir = Self::game_dir(&app, game_id).join(&safety);
+                if let Some(mut metadata) = SnapshotStore::read_metadata(&safety_dir) {
+                    metadata.restored_from = Some(timestamp.clone());
+                    let _ = SnapshotStore::write_metadata(&safety_dir, &metadata);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => return Err(format!("Snapshot de segurança falhou ({})", e)),
+        }
+
+        Self::restore_snapshot(&app, game_id, &snapshot_dir, &options)
+    }
+
+    /// Volta os saves para o último snapshot de segurança criado antes de uma restauração.
+    pub fn undo_last_restore(app: AppHandle, game_id: u32) -> Result<RestoreReport, String> {
+        let last_safety = SnapshotStore::snapshot_dirs(&Self::game_dir(&app, game_id))
+            .into_iter()
+            .rev()
+            .find(|dir| Self::snapshot_kind(dir) == SnapshotKind::PreRestore);
 
-        if !backup_root.exists() { return "Erro: Backup não encontrado.".to_string(); }
+        match last_safety {
+            Some(snapshot_dir) => Self::restore_snapshot(&app, game_id, &snapshot_dir, &RestoreOptions::default()),
+            None => Err("Nenhuma restauração para desfazer.".to_string()),
+        }
+    }
 
-        let mut restored = 0;
-        let mut options = fs_extra::dir::CopyOptions::new().overwrite(true).copy_inside(true);
-        options.content_only = true;
+    /// Prepara todos os destinos antes de tocar em qualquer save; se algum
+    /// falhar, nada é alterado e o relatório lista o erro de cada destino.
+    fn restore_snapshot(app: &AppHandle, game_id: u32, snapshot_dir: &Path, options: &RestoreOptions) -> Result<RestoreReport, String> {
+        let root = Self::backups_root(app);
+        let metadata = Self::select_files(SnapshotStore::load_or_import(&root, snapshot_dir)?, options);
 
-        if let Some(target) = SteamService::get_custom_path(&app, game_id) {
-            let source = backup_root.join("Custom_Saves");
-            if source.exists() && target.exists() {
-                let _ = fs_extra::dir::copy(&source, &target, &options);
-                restored += 1;
+        let mut report = RestoreReport::default();
+        let mut transaction = RestoreTransaction::new(&root);
+
+        for (folder, target) in Self::restore_targets(app, game_id, &metadata, options)? {
+            if !SnapshotStore::has_group(&metadata, &folder) { continue; }
+            match transaction.stage(&metadata, &folder, &target) {
+                Ok(count) => {
+                    report.restored_files += count;
+                    report.targets.push(target.to_string_lossy().to_string());
+                }
+                Err(error) => report.errors.push(RestoreTargetError {
+                    folder,
+                    target: target.to_string_lossy().to_string(),
+                    error,
+                }),
             }
         }
 
-        let manifest_paths = SteamService::get_manifest_paths(&app, game_id);
-        for (idx, target) in manifest_paths.iter().enumerate() {
-            let source = backup_root.join(format!("Game_Data_{}", idx));
-            if source.exists() && target.exists() {
-                let _ = fs_extra::dir::copy(&source, &target, &options);
-                restored += 1;
+        if report.targets.is_empty() && report.errors.is_empty() {
+            return Err("Nenhum destino para restaurar.".to_string());
+        }
+
+        if report.errors.is_empty() {
+            if let Err(error) = transaction.commit() {
+                report.errors.push(error);
+                report.rolled_back = true;
             }
+        } else {
+            transaction.abort();
         }
 
-        if restored > 0 { "Sucesso: Restaurado".to_string() } else { "Erro".to_string() }
+        report.success = report.errors.is_empty();
+        if !report.success {
+            report.restored_files = 0;
+        }
+        Ok(report)
     }
 
-    pub fn zip_for_cloud(game_name: String, timestamp: String) -> String {
-        let user_dirs = UserDirs::new().unwrap();
-        let doc_dir = user_dirs.document_dir().unwrap_or_else(|| user_dirs.home_dir());
-        let safe_name = game_name.replace(|c: char| !c.is_alphanumeric() && c != ' ', "_");
-        let bkp_path = doc_dir.join("SaveManagerBackups").join(&safe_name).join(&timestamp);
-        let zip_path = doc_dir.join("SaveManagerBackups").join(&safe_name).join(format!("{}.zip", timestamp));
+    /// Dry-run de `restore_backup`: usa a mesma resolução de destinos e
+    /// descreve o que aconteceria com cada arquivo, sem tocar no disco.
+    pub fn plan_restore(app: AppHandle, game_id: u32, timestamp: String, options: RestoreOptions) -> Result<RestorePlan, String> {
+        let root = Self::backups_root(&app);
+        let snapshot_dir = Self::game_dir(&app, game_id).join(&timestamp);
+        if !snapshot_dir.exists() { return Err("Backup não encontrado.".to_string()); }
+
+        let metadata = Self::select_files(SnapshotStore::load_or_import(&root, &snapshot_dir)?, &options);
+        let mut plan = RestorePlan { snapshot: timestamp, targets: Vec::new() };
+
+        for (folder, target) in Self::restore_targets(&app, game_id, &metadata, &options)? {
+            if !SnapshotStore::has_group(&metadata, &folder) { continue; }
+            let exists = target.exists();
+            let mut target_plan = RestoreTargetPlan {
+                folder: folder.clone(),
+                target: target.to_string_lossy().to_string(),
+                exists,
+                files: Vec::new(),
+            };
+
+            let group = format!("{}/", folder);
+            for file in metadata.files.iter().filter(|f| f.path.starts_with(&group)) {
+                let rel = &file.path[group.len()..];
+                target_plan.files.push(Self::plan_file(&target.join(rel), rel, file.size, file.mtime, &file.sha256));
+            }
+            plan.targets.push(target_plan);
+        }
+        Ok(plan)
+    }
+
+    fn plan_file(live: &Path, rel: &str, new_size: u64, new_mtime: i64, sha256: &str) -> PlannedFile {
+        let current = fs::metadata(live).ok().filter(|m| m.is_file());
+        let action = match &current {
+            None => PlannedAction::Create,
+            Some(m) if m.len() == new_size
+                && SnapshotStore::hash_file(live).is_ok_and(|h| h == sha256) => PlannedAction::Unchanged,
+            Some(_) => PlannedAction::Overwrite,
+        };
+        PlannedFile {
+            path: rel.to_string(),
+            target: live.to_string_lossy().to_string(),
+            action,
+            old_size: current.as_ref().map(|m| m.len()),
+            old_mtime: current.as_ref().map(SnapshotStore::mtime_secs),
+            new_size,
+            new_mtime,
+        }
+    }
+
+    /// Mantém só os arquivos pedidos em `options.paths` (arquivos ou pastas do snapshot).
+    fn select_files(mut metadata: SnapshotMetadata, options: &RestoreOptions) -> SnapshotMetadata {
+        if let Some(paths) = &options.paths {
+            metadata.files.retain(|f| {
+                paths.iter().any(|p| {
+                    let p = p.trim_end_matches('/');
+                    f.path == p || f.path.starts_with(&format!("{}/", p))
+                })
+            });
+        }
+        metadata
+    }
+
+    /// Conteúdo do snapshot como árvore, para escolher o que restaurar.
+    pub fn browse_backup(app: &AppHandle, game_id: u32, timestamp: String) -> Result<Vec<SnapshotTreeNode>, String> {
+        let snapshot_dir = Self::game_dir(app, game_id).join(&timestamp);
+        if !snapshot_dir.exists() { return Err("Backup não encontrado.".to_string()); }
+
+        let mut metadata = SnapshotStore::load_or_import(&Self::backups_root(app), &snapshot_dir)?;
+        metadata.files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut tree = Vec::new();
+        for file in &metadata.files {
+            let parts: Vec<&str> = file.path.split('/').collect();
+            Self::insert_tree_node(&mut tree, &parts, "", file);
+        }
+        Ok(tree)
+    }
+
+    fn insert_tree_node(nodes: &mut Vec<SnapshotTreeNode>, parts: &[&str], prefix: &str, file: &SnapshotFile) {
+        let name = parts[0];
+        let path = if prefix.is_empty() { name.to_string() } else { format!("{}/{}", prefix, name) };
+
+        if parts.len() == 1 {
+            nodes.push(SnapshotTreeNode {
+                name: name.to_string(),
+                path,
+                is_dir: false,
+                size: file.size,
+                mtime: file.mtime,
+                children: Vec::new(),
+            });
+            return;
+        }
+
+        let idx = match nodes.iter().position(|n| n.is_dir && n.name == name) {
+            Some(idx) => idx,
+            None => {
+                nodes.push(SnapshotTreeNode {
+                    name: name.to_string(),
+                    path: path.clone(),
+                    is_dir: true,
+                    size: 0,
+                    mtime: 0,
+                    children: Vec::new(),
+                });
+                nodes.len() - 1
+            }
+        };
+        let node = &mut nodes[idx];
+        node.size += file.size;
+        node.mtime = node.mtime.max(file.mtime);
+        Self::insert_tree_node(&mut node.children, &parts[1..], &path, file);
+    }
+
+    /// Destino de cada pasta do snapshot, ou `destination/<pasta>` quando a
+    /// restauração vai para um local escolhido pelo usuário.
+    fn restore_targets(app: &AppHandle, game_id: u32, metadata: &SnapshotMetadata, options: &RestoreOptions) -> Result<Vec<(String, PathBuf)>, String> {
+        let mut targets = Self::origin_targets(app, game_id, metadata, options)?;
+        if let Some(destination) = &options.destination {
+            for (folder, target) in targets.iter_mut() {
+                *target = Path::new(destination).join(folder.as_str());
+            }
+        }
+        Ok(targets)
+    }
+
+    /// Várias contas restauradas para uma mesma conta de destino se
+    /// sobrescreveriam, então a conta de origem precisa ser escolhida.
+    fn check_accounts(metadata: &SnapshotMetadata, options: &RestoreOptions) -> Result<(), String> {
+        let cloud_sources = metadata.sources.iter().filter(|s| s.kind == SourceKind::SteamCloud).count();
+        if options.target_account.is_some() && options.source_account.is_none() && cloud_sources > 1 {
+            return Err("O backup tem saves de mais de uma conta Steam; escolha a conta de origem.".to_string());
+        }
+        Ok(())
+    }
+
+    /// A pasta é mapeada pela origem gravada no `snapshot.json`, não pela
+    /// posição da chave no manifesto atual.
+    fn origin_targets(app: &AppHandle, game_id: u32, metadata: &SnapshotMetadata, options: &RestoreOptions) -> Result<Vec<(String, PathBuf)>, String> {
+        Self::check_accounts(metadata, options)?;
+        let mut targets = Vec::new();
+
+        // Snapshots importados de versões antigas não têm origem registrada
+        if metadata.sources.is_empty() {
+            // Variável sem valor (ex.: prefixo do Proton ainda não criado) deixa a
+            // raiz vazia ou relativa; esses destinos são pulados
+            if let Some(target) = SteamService::get_custom_path(app, game_id).filter(|t| t.is_absolute()) {
+                targets.push(("Custom_Saves".to_string(), target));
+            }
+            for (idx, (_, target)) in SteamService::get_legacy_manifest_entries(app, game_id).into_iter().enumerate() {
+                if target.is_absolute() {
+                    targets.push((format!("Game_Data_{}", idx), target));
+                }
+            }
+            match options.target_account {
+                Some(account) => targets.extend(
+                    SteamService::userdata_app_dir(account, game_id).map(|t| ("Steam_Cloud".to_string(), t))
+                ),
+                None => {
+                    let existing = SteamService::get_userdata_app_dirs(game_id);
+                    if existing.is_empty() {
+                        // Máquina nova: a pasta do jogo ainda não existe em nenhuma conta
+                        let accounts = SteamService::get_steam_accounts();
+                        let account = accounts.iter().find(|a| a.most_recent).or(accounts.first());
+                        targets.extend(
+                            account.and_then(|a| SteamService::userdata_app_dir(a.id3, game_id)).map(|t| ("Steam_Cloud".to_string(), t))
+                        );
+                    } else {
+                        targets.extend(existing.into_iter().map(|(_, t)| ("Steam_Cloud".to_string(), t)));
+                    }
+                }
+            }
+            return Ok(targets);
+        }
+
+        let manifest_entries = SteamService::get_manifest_entries(app, game_id);
+        for source in &metadata.sources {
+            let target = match source.kind {
+                SourceKind::Custom => SteamService::get_custom_path(app, game_id),
+                SourceKind::Manifest => source.pattern.as_ref()
+                    .and_then(|pattern| manifest_entries.iter().find(|(key, _)| key == pattern))
+                    .map(|(_, root)| match &source.matched {
+                        Some(rel) => root.join(rel),
+                        None => root.clone(),
+                    }),
+                SourceKind::SteamCloud => {
+                    // Snapshots anteriores à separação por conta não gravavam o account_id
+                    let account = source.account_id.or_else(|| {
+                        Path::new(&source.origin).parent()?.file_name()?.to_str()?.parse().ok()
+                    });
+                    if options.source_account.is_some() && account != options.source_account {
+                        continue;
+                    }
+                    options.target_account.or(account).and_then(|a| SteamService::userdata_app_dir(a, game_id))
+                }
+            };
+            // Raiz vazia ou relativa = variável não resolvida; usa a origem gravada
+            let target = target.filter(|t| t.is_absolute()).unwrap_or_else(|| PathBuf::from(&source.origin));
+            // Arquivos isolados são restaurados dentro da pasta onde estavam
+            let target = match (source.is_file, target.parent()) {
+                (true, Some(parent)) => parent.to_path_buf(),
+                _ => target,
+            };
+            targets.push((source.folder.clone(), target));
+        }
+        Ok(targets)
+    }
+
+    pub fn zip_for_cloud(app: &AppHandle, game_id: u32, timestamp: String) -> String {
+        let root = Self::backups_root(app);
+        let game_dir = Self::game_dir(app, game_id);
+        let bkp_path = game_dir.join(&timestamp);
+        let zip_path = game_dir.join(format!("{}.zip", timestamp));
 
         if !bkp_path.exists() { return "Erro: Pasta inexistente.".to_string(); }
 
+        let metadata = match SnapshotStore::load_or_import(&root, &bkp_path) {
+            Ok(m) => m,
+            Err(e) => return format!("Erro: {}", e),
+        };
+
         let file = File::create(&zip_path).unwrap();
         let mut zip = zip::ZipWriter::new(file);
         let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
 
-        fn zip_dir(dir: &Path, prefix: &str, zip: &mut zip::ZipWriter<File>, opt: SimpleFileOptions) -> Result<(), String> {
-            for entry in fs::read_dir(dir).map_err(|e| e.to_string())? {
-                let entry = entry.map_err(|e| e.to_string())?;
-                let path = entry.path();
+        let mut files: Vec<_> = metadata.files.iter().collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        for entry in files {
+            if let Err(e) = zip.start_file(entry.path.as_str(), options) { return e.to_string(); }
+            let mut blob = match File::open(SnapshotStore::blob_path(&root, &entry.sha256)) {
+                Ok(f) => f,
+                Err(e) => return format!("Erro: {}: {}", entry.path, e),
+            };
+            if let Err(e) = io::copy(&mut blob, &mut zip) { return e.to_string(); }
+        }
+
+        zip.finish().unwrap();
+        format!("Sucesso:{:?}", zip_path)
+    }
+
+    /// O que mudou do snapshot `from` para o `to` do mesmo jogo.
+    pub fn diff_backups(app: &AppHandle, game_id: u32, from: String, to: String, include_text: bool) -> Result<BackupDiff, String> {
+        let root = Self::backups_root(app);
+        let game_dir = Self::game_dir(app, game_id);
+        let (from_dir, to_dir) = (game_dir.join(&from), game_dir.join(&to));
+        if !from_dir.exists() || !to_dir.exists() { return Err("Backup não encontrado.".to_string()); }
+
+        let before = SnapshotStore::load_or_import(&root, &from_dir)?;
+        let after = SnapshotStore::load_or_import(&root, &to_dir)?;
+        Ok(DiffService::compare(&root, &before, &after, include_text))
+    }
+
+    pub fn verify_backup(app: &AppHandle, game_id: u32, timestamp: String) -> Result<VerifyReport, String> {
+        let game_dir = Self::game_dir(app, game_id);
+        let snapshot_dir = game_dir.join(&timestamp);
+        if !snapshot_dir.exists() { return Err("Backup não encontrado.".to_string()); }
+        Ok(Self::verify_snapshot(&Self::backups_root(app), &snapshot_dir, &mut HashMap::new()))
+    }
+
+    /// Verifica todos os snapshots de todos os jogos, emitindo `verify-progress`.
+    pub fn verify_all_backups(window: Window) -> Vec<VerifyReport> {
+        let root = Self::backups_root(window.app_handle());
+        let snapshots: Vec<PathBuf> = SnapshotStore::game_dirs(&root)
+            .iter()
+            .flat_map(|game_dir| SnapshotStore::snapshot_dirs(game_dir))
+            .collect();
+
+        // Blobs são compartilhados entre snapshots: cada um é re-hasheado uma vez só
+        let mut checked_blobs = HashMap::new();
+        let mut reports = Vec::new();
+        for (idx, snapshot_dir) in snapshots.iter().enumerate() {
+            let report = Self::verify_snapshot(&root, snapshot_dir, &mut checked_blobs);
+            let _ = window.emit("verify-progress", VerifyProgress {
+                current: idx + 1,
+                total: snapshots.len(),
+                game: report.game.clone(),
+                snapshot: report.snapshot.clone(),
+            });
+            reports.push(report);
+        }
+        reports
+    }
+
+    fn verify_snapshot(root: &Path, snapshot_dir: &Path, checked_blobs: &mut HashMap<String, Option<bool>>) -> VerifyReport {
+        let name_of = |p: &Path| p.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let game_dir = snapshot_dir.parent().unwrap_or(snapshot_dir);
+        let mut report = VerifyReport {
+            game: name_of(game_dir),
+            snapshot: name_of(snapshot_dir),
+            files_checked: 0,
+            store: IntegrityIssues::default(),
+            zip: None,
+            ok: false,
+        };
+
+        let metadata = match SnapshotStore::read_metadata(snapshot_dir) {
+            Some(m) => m,
+            None => {
+                report.store.missing.push(METADATA_FILE.to_string());
+                return report;
+            }
+        };
+        if !metadata.game_name.is_empty() {
+            report.game = metadata.game_name.clone();
+        }
+
+        for file in &metadata.files {
+            let state = *checked_blobs
+                .entry(file.sha256.clone())
+                .or_insert_with(|| SnapshotStore::verify_blob(root, &file.sha256));
+            match state {
+                None => report.store.missing.push(file.path.clone()),
+                Some(false) => report.store.modified.push(file.path.clone()),
+                Some(true) => {}
+            }
+            report.files_checked += 1;
+        }
+
+        // Qualquer coisa além do snapshot.json na pasta do snapshot é sobra
+        if let Ok(entries) = fs::read_dir(snapshot_dir) {
+            for entry in entries.flatten() {
                 let name = entry.file_name().to_string_lossy().to_string();
-                let zip_name = if prefix.is_empty() { name } else { format!("{}/{}", prefix, name) };
-                if path.is_dir() {
-                    zip.add_directory(&zip_name, opt).map_err(|e| e.to_string())?;
-                    zip_dir(&path, &zip_name, zip, opt)?;
-                } else {
-                    zip.start_file(&zip_name, opt).map_err(|e| e.to_string())?;
-                    let mut f = File::open(path).map_err(|e| e.to_string())?;
-                    let mut buf = Vec::new();
-                    f.read_to_end(&mut buf).map_err(|e| e.to_string())?;
-                    zip.write_all(&buf).map_err(|e| e.to_string())?;
+                if name != METADATA_FILE {
+                    report.store.extra.push(name);
                 }
             }
-            Ok(())
         }
 
-        if let Err(e) = zip_dir(&bkp_path, "", &mut zip, options) { return e; }
-        zip.finish().unwrap();
-        format!("Sucesso:{:?}", zip_path)
+        let zip_path = game_dir.join(format!("{}.zip", report.snapshot));
+        if zip_path.exists() {
+            report.zip = Some(Self::verify_zip(&zip_path, &metadata));
+        }
+
+        report.ok = report.store.is_empty() && report.zip.as_ref().is_none_or(|z| z.is_empty());
+        report
+    }
+
+    /// Lê cada entrada do zip até o fim (o que valida o CRC) e compara o SHA-256
+    /// com o registrado no snapshot.
+    fn verify_zip(zip_path: &Path, metadata: &SnapshotMetadata) -> IntegrityIssues {
+        let mut issues = IntegrityIssues::default();
+        let mut archive = match File::open(zip_path).map_err(|e| e.to_string())
+            .and_then(|f| zip::ZipArchive::new(f).map_err(|e| e.to_string()))
+        {
+            Ok(a) => a,
+            Err(e) => {
+                issues.modified.push(format!("{}: {}", zip_path.to_string_lossy(), e));
+                return issues;
+            }
+        };
+
+        let expected: HashMap<&str, &str> = metadata.files.iter()
+            .map(|f| (f.path.as_str(), f.sha256.as_str()))
+            .collect();
+        let mut seen = HashSet::new();
+
+        for i in 0..archive.len() {
+            let mut entry = match archive.by_index(i) {
+                Ok(e) => e,
+                Err(e) => {
+                    issues.modified.push(format!("#{}: {}", i, e));
+                    continue;
+                }
+            };
+            if entry.is_dir() { continue; }
+            let name = entry.name().to_string();
+
+            let mut hasher = Sha256::new();
+            let hash = io::copy(&mut entry, &mut hasher).map(|_| hex::encode(hasher.finalize()));
+
+            match expected.get(name.as_str()) {
+                None => issues.extra.push(name.clone()),
+                Some(sha256) => {
+                    if !hash.is_ok_and(|h| h == *sha256) {
+                        issues.modified.push(name.clone());
+                    }
+                }
+            }
+            seen.insert(name);
+        }
+
+        for file in &metadata.files {
+            if !seen.contains(&file.path) {
+                issues.missing.push(file.path.clone());
+            }
+        }
+        issues
     }
-}
\ No newline at end of file
+}
@@ -1,15 +1,88 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use tauri::{AppHandle, Manager};
+use crate::models::{FileRules, RetentionPolicy, WatchSettings};
+use crate::services::storage_service::StorageQuota;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct AppConfig {
     pub retention_limit: usize,
+    /// Snapshots extras mantidos por hora/dia/semana (0 desativa).
+    pub retention_hourly: usize,
+    pub retention_daily: usize,
+    pub retention_weekly: usize,
+    /// Cotas de espaço em MB, aplicadas após cada backup.
+    pub game_quota_mb: Option<u64>,
+    pub total_quota_mb: Option<u64>,
+    /// Inclui arquivos marcados só como `config` no manifesto Ludusavi.
+    pub include_config_files: bool,
+    /// Excluídos de todos os jogos.
+    pub default_excludes: Vec<String>,
+    /// Regras por app id, somadas às do `custom_manifest.json`.
+    pub game_rules: HashMap<u32, FileRules>,
+    /// Jogos com auto-backup ativo (app id → nome), reativados ao abrir o app.
+    pub auto_backup_games: HashMap<u32, String>,
+    /// Debounce do auto-backup por app id.
+    pub watch_settings: HashMap<u32, WatchSettings>,
+    /// Pasta onde fica a `SaveManagerBackups`; `None` usa `Documents`.
+    pub backup_root: Option<String>,
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
-        Self { retention_limit: 10 }
+        Self {
+            retention_limit: 10,
+            retention_hourly: 0,
+            retention_daily: 0,
+            retention_weekly: 0,
+            game_quota_mb: None,
+            total_quota_mb: None,
+            include_config_files: false,
+            default_excludes: vec!["*.log".to_string(), "Crashes/".to_string()],
+            game_rules: HashMap::new(),
+            auto_backup_games: HashMap::new(),
+            watch_settings: HashMap::new(),
+            backup_root: None,
+        }
+    }
+}
+
+/// Campos alterados pela tela de configurações; `None` mantém o valor atual.
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct AppConfigUpdate {
+    pub retention_limit: Option<usize>,
+    pub retention_hourly: Option<usize>,
+    pub retention_daily: Option<usize>,
+    pub retention_weekly: Option<usize>,
+    /// 0 desativa a cota.
+    pub game_quota_mb: Option<u64>,
+    pub total_quota_mb: Option<u64>,
+    pub default_excludes: Option<Vec<String>>,
+    pub include_config_files: Option<bool>,
+}
+
+impl AppConfig {
+    pub fn retention_policy(&self) -> RetentionPolicy {
+        RetentionPolicy {
+            keep_last: self.retention_limit,
+            hourly: self.retention_hourly,
+            daily: self.retention_daily,
+            weekly: self.retention_weekly,
+        }
+    }
+
+    pub fn watch_settings(&self, game_id: u32) -> WatchSettings {
+        self.watch_settings.get(&game_id).copied().unwrap_or_default()
+    }
+
+    pub fn storage_quota(&self) -> StorageQuota {
+        StorageQuota {
+            per_game_mb: self.game_quota_mb.filter(|mb| *mb > 0),
+            total_mb: self.total_quota_mb.filter(|mb| *mb > 0),
+        }
     }
 }
 
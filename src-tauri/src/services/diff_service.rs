@@ -0,0 +1,251 @@
+// src-tauri/src/services/diff_service.rs
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use crate::models::{BackupDiff, FileChange, FileDiff, SnapshotFile, SnapshotMetadata};
+use crate::services::snapshot_store::SnapshotStore;
+
+const TEXT_EXTENSIONS: [&str; 4] = ["ini", "json", "xml", "cfg"];
+const MAX_TEXT_DIFF_BYTES: u64 = 1024 * 1024;
+const CONTEXT_LINES: usize = 3;
+// Limite da tabela de LCS (linhas antigas x linhas novas)
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+enum Op {
+    Equal(usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+pub struct DiffService;
+
+impl DiffService {
+    /// Compara dois snapshots pelo caminho de cada arquivo. Com `include_text`,
+    /// arquivos de texto modificados ganham um diff unificado por linha.
+    pub fn compare(backups_root: &Path, from: &SnapshotMetadata, to: &SnapshotMetadata, include_text: bool) -> BackupDiff {
+        let old: HashMap<&str, &SnapshotFile> = from.files.iter().map(|f| (f.path.as_str(), f)).collect();
+        let new: HashMap<&str, &SnapshotFile> = to.files.iter().map(|f| (f.path.as_str(), f)).collect();
+
+        let mut files = Vec::new();
+        for (path, before) in &old {
+            match new.get(path) {
+                None => files.push(FileDiff {
+                    path: path.to_string(),
+                    change: FileChange::Removed,
+                    old_size: Some(before.size),
+                    new_size: None,
+                    size_delta: -(before.size as i64),
+                    unified_diff: None,
+                }),
+                Some(after) if after.sha256 != before.sha256 => {
+                    let unified_diff = if include_text && Self::is_text(path, before, after) {
+                        Self::text_diff(backups_root, before, after)
+                    } else {
+                        None
+                    };
+                    files.push(FileDiff {
+                        path: path.to_string(),
+                        change: FileChange::Modified,
+                        old_size: Some(before.size),
+                        new_size: Some(after.size),
+                        size_delta: after.size as i64 - before.size as i64,
+                        unified_diff,
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+        for (path, after) in &new {
+            if !old.contains_key(path) {
+                files.push(FileDiff {
+                    path: path.to_string(),
+                    change: FileChange::Added,
+                    old_size: None,
+                    new_size: Some(after.size),
+                    size_delta: after.size as i64,
+                    unified_diff: None,
+                });
+            }
+        }
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        BackupDiff {
+            total_size_delta: files.iter().map(|f| f.size_delta).sum(),
+            files,
+        }
+    }
+
+    fn is_text(path: &str, before: &SnapshotFile, after: &SnapshotFile) -> bool {
+        let ext = Path::new(path).extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        TEXT_EXTENSIONS.contains(&ext.as_str())
+            && before.size <= MAX_TEXT_DIFF_BYTES
+            && after.size <= MAX_TEXT_DIFF_BYTES
+    }
+
+    fn text_diff(backups_root: &Path, before: &SnapshotFile, after: &SnapshotFile) -> Option<String> {
+        let old = fs::read(SnapshotStore::blob_path(backups_root, &before.sha256)).ok()?;
+        let new = fs::read(SnapshotStore::blob_path(backups_root, &after.sha256)).ok()?;
+        Self::unified_diff(
+            &String::from_utf8_lossy(&old),
+            &String::from_utf8_lossy(&new),
+            &before.path,
+            &after.path,
+        )
+    }
+
+    /// `None` para arquivos grandes demais ou quando nenhuma linha muda (só
+    /// quebras de linha CRLF ou a quebra final).
+    pub fn unified_diff(old: &str, new: &str, old_name: &str, new_name: &str) -> Option<String> {
+        let a: Vec<&str> = old.lines().collect();
+        let b: Vec<&str> = new.lines().collect();
+        if a.len().saturating_mul(b.len()) > MAX_DIFF_CELLS { return None; }
+
+        let ops = Self::diff_ops(&a, &b);
+        let is_equal = |k: usize| matches!(ops[k], Op::Equal(_));
+        if (0..ops.len()).all(is_equal) { return None; }
+
+        // Quantas linhas de cada lado foram consumidas antes da operação k
+        let mut old_before = vec![0usize; ops.len() + 1];
+        let mut new_before = vec![0usize; ops.len() + 1];
+        for (k, op) in ops.iter().enumerate() {
+            let (o, n) = match op {
+                Op::Equal(_) => (1, 1),
+                Op::Delete(_) => (1, 0),
+                Op::Insert(_) => (0, 1),
+            };
+            old_before[k + 1] = old_before[k] + o;
+            new_before[k + 1] = new_before[k] + n;
+        }
+
+        let mut out = format!("--- {}\n+++ {}\n", old_name, new_name);
+        let mut idx = 0;
+        while idx < ops.len() {
+            let first_change = match (idx..ops.len()).find(|&k| !is_equal(k)) {
+                Some(k) => k,
+                None => break,
+            };
+
+            // Junta mudanças separadas por até 2x o contexto num mesmo hunk
+            let mut k = first_change;
+            let last_change_end;
+            loop {
+                while k < ops.len() && !is_equal(k) { k += 1; }
+                let change_end = k;
+                let mut run = 0;
+                while k + run < ops.len() && is_equal(k + run) { run += 1; }
+                if k + run < ops.len() && run <= 2 * CONTEXT_LINES {
+                    k += run;
+                    continue;
+                }
+                last_change_end = change_end;
+                break;
+            }
+
+            let start = first_change.saturating_sub(CONTEXT_LINES).max(idx);
+            let end = (last_change_end + CONTEXT_LINES).min(ops.len());
+            let old_len = old_before[end] - old_before[start];
+            let new_len = new_before[end] - new_before[start];
+            let old_start = if old_len > 0 { old_before[start] + 1 } else { old_before[start] };
+            let new_start = if new_len > 0 { new_before[start] + 1 } else { new_before[start] };
+            out.push_str(&format!("@@ -{},{} +{},{} @@\n", old_start, old_len, new_start, new_len));
+
+            for op in &ops[start..end] {
+                match op {
+                    Op::Equal(i) => out.push_str(&format!(" {}\n", a[*i])),
+                    Op::Delete(i) => out.push_str(&format!("-{}\n", a[*i])),
+                    Op::Insert(j) => out.push_str(&format!("+{}\n", b[*j])),
+                }
+            }
+            idx = end;
+        }
+        Some(out)
+    }
+
+    /// Sequência de edições via LCS (arquivos de save em texto são pequenos).
+    fn diff_ops(a: &[&str], b: &[&str]) -> Vec<Op> {
+        let (n, m) = (a.len(), b.len());
+        let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if a[i] == b[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let mut ops = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < n && j < m {
+            if a[i] == b[j] {
+                ops.push(Op::Equal(i));
+                i += 1;
+                j += 1;
+            } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+                ops.push(Op::Delete(i));
+                i += 1;
+            } else {
+                ops.push(Op::Insert(j));
+                j += 1;
+            }
+        }
+        ops.extend((i..n).map(Op::Delete));
+        ops.extend((j..m).map(Op::Insert));
+        ops
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DiffService;
+
+    fn diff(old: &str, new: &str) -> Option<String> {
+        DiffService::unified_diff(old, new, "a.ini", "b.ini")
+    }
+
+    fn numbered(changes: &[(usize, &str)]) -> String {
+        (1..=20)
+            .map(|n| changes.iter().find(|(line, _)| *line == n).map_or(n.to_string(), |(_, text)| text.to_string()))
+            .map(|line| line + "\n")
+            .collect()
+    }
+
+    #[test]
+    fn nearby_changes_share_a_hunk() {
+        let out = diff(&numbered(&[]), &numbered(&[(5, "five"), (11, "eleven")])).unwrap();
+        assert_eq!(out.matches("@@ -").count(), 1);
+        assert!(out.contains("@@ -2,13 +2,13 @@\n"));
+    }
+
+    #[test]
+    fn distant_changes_get_separate_hunks() {
+        let out = diff(&numbered(&[]), &numbered(&[(2, "two"), (15, "fifteen")])).unwrap();
+        assert!(out.contains("@@ -1,5 +1,5 @@\n"));
+        assert!(out.contains("@@ -12,7 +12,7 @@\n"));
+    }
+
+    #[test]
+    fn pure_insert() {
+        assert_eq!(diff("a\nb\n", "a\nb\nc\n").unwrap(), "--- a.ini\n+++ b.ini\n@@ -1,2 +1,3 @@\n a\n b\n+c\n");
+    }
+
+    #[test]
+    fn pure_delete() {
+        assert_eq!(diff("a\nb\nc\n", "a\nc\n").unwrap(), "--- a.ini\n+++ b.ini\n@@ -1,3 +1,2 @@\n a\n-b\n c\n");
+    }
+
+    #[test]
+    fn empty_side() {
+        assert_eq!(diff("", "x\ny\n").unwrap(), "--- a.ini\n+++ b.ini\n@@ -0,0 +1,2 @@\n+x\n+y\n");
+        assert_eq!(diff("x\ny\n", "").unwrap(), "--- a.ini\n+++ b.ini\n@@ -1,2 +0,0 @@\n-x\n-y\n");
+    }
+
+    #[test]
+    fn line_ending_changes_have_no_diff() {
+        assert_eq!(diff("a\r\nb\r\n", "a\nb\n"), None);
+        assert_eq!(diff("a\nb", "a\nb\n"), None);
+    }
+}
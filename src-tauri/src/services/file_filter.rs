@@ -0,0 +1,66 @@
+// src-tauri/src/services/file_filter.rs
+use glob::{MatchOptions, Pattern};
+use crate::models::FileRules;
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Um padrão no estilo `.gitignore`: `Crashes/` só casa com pastas e
+/// padrões sem `/` (como `*.log`) casam com o nome em qualquer nível.
+struct Rule {
+    pattern: Pattern,
+    dir_only: bool,
+    anchored: bool,
+}
+
+impl Rule {
+    fn parse(raw: &str) -> Option<Rule> {
+        let raw = raw.trim().replace('\\', "/");
+        let dir_only = raw.ends_with('/');
+        let body = raw.trim_matches('/');
+        if body.is_empty() { return None; }
+        Some(Rule {
+            pattern: Pattern::new(body).ok()?,
+            dir_only,
+            anchored: body.contains('/'),
+        })
+    }
+
+    fn matches(&self, rel: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir { return false; }
+        let subject = if self.anchored { rel } else { rel.rsplit('/').next().unwrap_or(rel) };
+        self.pattern.matches_with(subject, MATCH_OPTIONS)
+    }
+}
+
+/// Regras de inclusão/exclusão aplicadas aos caminhos relativos à origem.
+/// A exclusão sempre vence; sem regras de inclusão, tudo é incluído.
+#[derive(Default)]
+pub struct FileFilter {
+    include: Vec<Rule>,
+    exclude: Vec<Rule>,
+}
+
+impl FileFilter {
+    pub fn new(rules: &FileRules) -> Self {
+        Self {
+            include: rules.include.iter().filter_map(|r| Rule::parse(r)).collect(),
+            exclude: rules.exclude.iter().filter_map(|r| Rule::parse(r)).collect(),
+        }
+    }
+
+    pub fn has_includes(&self) -> bool {
+        !self.include.is_empty()
+    }
+
+    pub fn excludes(&self, rel: &str, is_dir: bool) -> bool {
+        self.exclude.iter().any(|r| r.matches(rel, is_dir))
+    }
+
+    pub fn includes(&self, rel: &str, is_dir: bool) -> bool {
+        self.include.iter().any(|r| r.matches(rel, is_dir))
+    }
+}
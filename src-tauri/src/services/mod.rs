@@ -1,5 +1,14 @@
 pub mod steam_service;
+pub mod placeholders;
 pub mod backup_service;
+pub mod snapshot_store;
+pub mod file_filter;
+pub mod restore_transaction;
+pub mod diff_service;
 pub mod cloud_service;
 pub mod watcher_service;
-pub mod config_service;
\ No newline at end of file
+pub mod session_service;
+pub mod wrap_service;
+pub mod config_service;
+pub mod retention_service;
+pub mod storage_service;
\ No newline at end of file
@@ -0,0 +1,287 @@
+// src-tauri/src/services/placeholders.rs
+
+/// Valores das variáveis dos caminhos do manifesto Ludusavi. Campos `None`
+/// deixam a variável sem resolver (o caminho é descartado mais adiante).
+#[derive(Debug, Clone, Default)]
+pub struct PlaceholderContext {
+    pub home: Option<String>,
+    /// `<home>` dos caminhos Windows: a pasta do usuário no prefixo do Proton.
+    pub win_home: Option<String>,
+    /// Pasta da biblioteca Steam onde o jogo está instalado.
+    pub root: Option<String>,
+    /// Nome da pasta de instalação (`steamapps/common/<game>`).
+    pub game: Option<String>,
+    pub base: Option<String>,
+    pub store_game_id: Option<String>,
+    /// Contas encontradas em `userdata`; vazio vira curinga.
+    pub store_user_ids: Vec<String>,
+    pub os_user_name: Option<String>,
+    pub xdg_data: Option<String>,
+    pub xdg_config: Option<String>,
+    pub win_app_data: Option<String>,
+    pub win_local_app_data: Option<String>,
+    pub win_local_app_data_low: Option<String>,
+    pub win_documents: Option<String>,
+    pub win_public: Option<String>,
+    pub win_program_data: Option<String>,
+    pub win_dir: Option<String>,
+    /// Raiz do drive `C:` (o `drive_c` do prefixo no Proton).
+    pub win_drive: Option<String>,
+}
+
+impl PlaceholderContext {
+    /// Aplica `f` a todos os valores (ex.: escapar curingas antes de usar como glob).
+    pub fn map_values(&self, f: impl Fn(&str) -> String) -> PlaceholderContext {
+        let map = |value: &Option<String>| value.as_deref().map(&f);
+        PlaceholderContext {
+            home: map(&self.home),
+            win_home: map(&self.win_home),
+            root: map(&self.root),
+            game: map(&self.game),
+            base: map(&self.base),
+            store_game_id: map(&self.store_game_id),
+            store_user_ids: self.store_user_ids.iter().map(|id| f(id)).collect(),
+            os_user_name: map(&self.os_user_name),
+            xdg_data: map(&self.xdg_data),
+            xdg_config: map(&self.xdg_config),
+            win_app_data: map(&self.win_app_data),
+            win_local_app_data: map(&self.win_local_app_data),
+            win_local_app_data_low: map(&self.win_local_app_data_low),
+            win_documents: map(&self.win_documents),
+            win_public: map(&self.win_public),
+            win_program_data: map(&self.win_program_data),
+            win_dir: map(&self.win_dir),
+            win_drive: map(&self.win_drive),
+        }
+    }
+}
+
+const WINDOWS_MARKERS: [&str; 5] = ["<win", "%", "AppData", "Saved Games", "My Documents"];
+
+/// Expande as variáveis de `path`. Retorna um caminho por conta Steam
+/// quando o caminho usa `<storeUserId>`; curingas são preservados.
+pub fn expand_placeholders(path: &str, ctx: &PlaceholderContext) -> Vec<String> {
+    let mut resolved = path.replace('\\', "/");
+
+    // No Proton, `<home>` de caminhos Windows é a pasta do usuário no prefixo
+    let windows_path = WINDOWS_MARKERS.iter().any(|m| resolved.contains(m)) || has_drive_letter(&resolved);
+    let home = if windows_path { ctx.win_home.as_ref().or(ctx.home.as_ref()) } else { ctx.home.as_ref() };
+
+    let replacements: [(&str, Option<&String>); 21] = [
+        ("<base>", ctx.base.as_ref()),
+        ("<root>", ctx.root.as_ref()),
+        ("<game>", ctx.game.as_ref()),
+        ("<home>", home),
+        ("<storeGameId>", ctx.store_game_id.as_ref()),
+        ("<osUserName>", ctx.os_user_name.as_ref()),
+        ("<xdgData>", ctx.xdg_data.as_ref()),
+        ("<xdgConfig>", ctx.xdg_config.as_ref()),
+        ("<winAppData>", ctx.win_app_data.as_ref()),
+        ("<winLocalAppDataLow>", ctx.win_local_app_data_low.as_ref()),
+        ("<winLocalAppData>", ctx.win_local_app_data.as_ref()),
+        ("<winDocuments>", ctx.win_documents.as_ref()),
+        ("<winPublic>", ctx.win_public.as_ref()),
+        ("<winProgramData>", ctx.win_program_data.as_ref()),
+        ("<winDir>", ctx.win_dir.as_ref()),
+        ("%USERPROFILE%", home),
+        ("%APPDATA%", ctx.win_app_data.as_ref()),
+        ("%LOCALAPPDATA%", ctx.win_local_app_data.as_ref()),
+        ("%DOCUMENTS%", ctx.win_documents.as_ref()),
+        ("%PUBLIC%", ctx.win_public.as_ref()),
+        ("%PROGRAMDATA%", ctx.win_program_data.as_ref()),
+    ];
+    for (token, value) in replacements {
+        if let Some(value) = value {
+            resolved = resolved.replace(token, normalize(value).as_str());
+        }
+    }
+    if let Some(dir) = &ctx.win_dir {
+        resolved = resolved.replace("%WINDIR%", normalize(dir).as_str());
+    }
+
+    if let (true, Some(drive)) = (has_drive_letter(&resolved), &ctx.win_drive) {
+        if resolved[..1].eq_ignore_ascii_case("c") {
+            resolved = format!("{}{}", normalize(drive), &resolved[2..]);
+        }
+    }
+
+    while resolved.contains("//") {
+        resolved = resolved.replace("//", "/");
+    }
+
+    if !resolved.contains("<storeUserId>") {
+        return vec![resolved];
+    }
+    if ctx.store_user_ids.is_empty() {
+        return vec![resolved.replace("<storeUserId>", "*")];
+    }
+    ctx.store_user_ids.iter().map(|id| resolved.replace("<storeUserId>", id)).collect()
+}
+
+fn normalize(value: &str) -> String {
+    value.replace('\\', "/").trim_end_matches('/').to_string()
+}
+
+fn has_drive_letter(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> PlaceholderContext {
+        PlaceholderContext {
+            home: Some("/home/deck".to_string()),
+            win_home: Some("/pfx/drive_c/users/steamuser".to_string()),
+            root: Some("/mnt/sd/SteamLibrary".to_string()),
+            game: Some("Hollow Knight".to_string()),
+            base: Some("/mnt/sd/SteamLibrary/steamapps/common/Hollow Knight".to_string()),
+            store_game_id: Some("367520".to_string()),
+            store_user_ids: vec!["11111".to_string(), "22222".to_string()],
+            os_user_name: Some("deck".to_string()),
+            xdg_data: Some("/home/deck/.local/share".to_string()),
+            xdg_config: Some("/home/deck/.config".to_string()),
+            win_app_data: Some("/pfx/drive_c/users/steamuser/AppData/Roaming".to_string()),
+            win_local_app_data: Some("/pfx/drive_c/users/steamuser/AppData/Local".to_string()),
+            win_local_app_data_low: Some("/pfx/drive_c/users/steamuser/AppData/LocalLow".to_string()),
+            win_documents: Some("/pfx/drive_c/users/steamuser/Documents".to_string()),
+            win_public: Some("/pfx/drive_c/users/Public".to_string()),
+            win_program_data: Some("/pfx/drive_c/ProgramData".to_string()),
+            win_dir: Some("/pfx/drive_c/windows".to_string()),
+            win_drive: Some("/pfx/drive_c".to_string()),
+        }
+    }
+
+    fn expand_one(path: &str) -> String {
+        let expanded = expand_placeholders(path, &ctx());
+        assert_eq!(expanded.len(), 1, "{:?}", expanded);
+        expanded.into_iter().next().unwrap()
+    }
+
+    #[test]
+    fn base() {
+        assert_eq!(expand_one("<base>/saves"), "/mnt/sd/SteamLibrary/steamapps/common/Hollow Knight/saves");
+    }
+
+    #[test]
+    fn root_and_game() {
+        assert_eq!(expand_one("<root>/steamapps/common/<game>/save.dat"), "/mnt/sd/SteamLibrary/steamapps/common/Hollow Knight/save.dat");
+    }
+
+    #[test]
+    fn home_native() {
+        assert_eq!(expand_one("<home>/.local/share/Game"), "/home/deck/.local/share/Game");
+    }
+
+    #[test]
+    fn home_windows_path_uses_prefix() {
+        assert_eq!(expand_one("<home>/Saved Games/Game"), "/pfx/drive_c/users/steamuser/Saved Games/Game");
+    }
+
+    #[test]
+    fn store_game_id() {
+        assert_eq!(expand_one("<root>/steamapps/compatdata/<storeGameId>"), "/mnt/sd/SteamLibrary/steamapps/compatdata/367520");
+    }
+
+    #[test]
+    fn store_user_id_expands_per_account() {
+        assert_eq!(
+            expand_placeholders("<root>/userdata/<storeUserId>/367520/remote", &ctx()),
+            vec![
+                "/mnt/sd/SteamLibrary/userdata/11111/367520/remote".to_string(),
+                "/mnt/sd/SteamLibrary/userdata/22222/367520/remote".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn store_user_id_without_accounts_is_wildcard() {
+        let ctx = PlaceholderContext { store_user_ids: Vec::new(), ..ctx() };
+        assert_eq!(expand_placeholders("<root>/userdata/<storeUserId>", &ctx), vec!["/mnt/sd/SteamLibrary/userdata/*".to_string()]);
+    }
+
+    #[test]
+    fn os_user_name() {
+        assert_eq!(expand_one("/tmp/<osUserName>/save"), "/tmp/deck/save");
+    }
+
+    #[test]
+    fn xdg_data() {
+        assert_eq!(expand_one("<xdgData>/Game"), "/home/deck/.local/share/Game");
+    }
+
+    #[test]
+    fn xdg_config() {
+        assert_eq!(expand_one("<xdgConfig>/Game/settings.ini"), "/home/deck/.config/Game/settings.ini");
+    }
+
+    #[test]
+    fn win_app_data() {
+        assert_eq!(expand_one("<winAppData>/Game"), "/pfx/drive_c/users/steamuser/AppData/Roaming/Game");
+    }
+
+    #[test]
+    fn win_local_app_data() {
+        assert_eq!(expand_one("<winLocalAppData>/Game"), "/pfx/drive_c/users/steamuser/AppData/Local/Game");
+    }
+
+    #[test]
+    fn win_local_app_data_low() {
+        assert_eq!(expand_one("<winLocalAppDataLow>/Team Cherry"), "/pfx/drive_c/users/steamuser/AppData/LocalLow/Team Cherry");
+    }
+
+    #[test]
+    fn win_documents() {
+        assert_eq!(expand_one("<winDocuments>/My Games/Game"), "/pfx/drive_c/users/steamuser/Documents/My Games/Game");
+    }
+
+    #[test]
+    fn win_public() {
+        assert_eq!(expand_one("<winPublic>/Documents/Game"), "/pfx/drive_c/users/Public/Documents/Game");
+    }
+
+    #[test]
+    fn win_program_data() {
+        assert_eq!(expand_one("<winProgramData>/Game"), "/pfx/drive_c/ProgramData/Game");
+    }
+
+    #[test]
+    fn win_dir() {
+        assert_eq!(expand_one("<winDir>/Game.ini"), "/pfx/drive_c/windows/Game.ini");
+    }
+
+    #[test]
+    fn percent_variables() {
+        assert_eq!(expand_one("%APPDATA%\\Game"), "/pfx/drive_c/users/steamuser/AppData/Roaming/Game");
+        assert_eq!(expand_one("%LOCALAPPDATA%\\Game"), "/pfx/drive_c/users/steamuser/AppData/Local/Game");
+        assert_eq!(expand_one("%USERPROFILE%\\Game"), "/pfx/drive_c/users/steamuser/Game");
+    }
+
+    #[test]
+    fn drive_letter_maps_to_prefix() {
+        assert_eq!(expand_one("C:\\Games\\Save"), "/pfx/drive_c/Games/Save");
+    }
+
+    #[test]
+    fn unknown_values_stay_unresolved() {
+        let ctx = PlaceholderContext::default();
+        assert_eq!(expand_placeholders("<winAppData>/Game", &ctx), vec!["<winAppData>/Game".to_string()]);
+    }
+
+    #[test]
+    fn mapped_values_keep_key_wildcards() {
+        let ctx = PlaceholderContext { game: Some("Game [Beta]".to_string()), ..ctx() };
+        let escaped = ctx.map_values(|v| v.replace('[', "[[]"));
+        assert_eq!(
+            expand_placeholders("<root>/steamapps/common/<game>/*.sav", &escaped),
+            vec!["/mnt/sd/SteamLibrary/steamapps/common/Game [[]Beta]/*.sav".to_string()]
+        );
+    }
+
+    #[test]
+    fn wildcards_are_preserved() {
+        assert_eq!(expand_one("<base>/*/save?.dat"), "/mnt/sd/SteamLibrary/steamapps/common/Hollow Knight/*/save?.dat");
+    }
+}
@@ -0,0 +1,149 @@
+// src-tauri/src/services/restore_transaction.rs
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::models::{RestoreTargetError, SnapshotMetadata};
+use crate::services::snapshot_store::SnapshotStore;
+
+struct StagedTarget {
+    folder: String,
+    target: PathBuf,
+    staging: PathBuf,
+    rollback: PathBuf,
+}
+
+/// Arquivo já trocado durante o commit: `saved` guarda a versão anterior, se havia.
+struct JournalEntry {
+    live: PathBuf,
+    saved: Option<PathBuf>,
+}
+
+/// Restauração em duas fases: os arquivos são primeiro gravados numa pasta
+/// irmã de cada destino e só então trocados pelos atuais. Se qualquer troca
+/// falhar, tudo o que já foi trocado volta ao estado anterior.
+pub struct RestoreTransaction {
+    backups_root: PathBuf,
+    staged: Vec<StagedTarget>,
+    journal: Vec<JournalEntry>,
+    created: Vec<PathBuf>,
+}
+
+impl RestoreTransaction {
+    pub fn new(backups_root: &Path) -> Self {
+        Self {
+            backups_root: backups_root.to_path_buf(),
+            staged: Vec::new(),
+            journal: Vec::new(),
+            created: Vec::new(),
+        }
+    }
+
+    fn sibling(target: &Path, tag: &str) -> Result<PathBuf, String> {
+        let name = target.file_name()
+            .ok_or(format!("Destino inválido: {}", target.to_string_lossy()))?
+            .to_string_lossy()
+            .to_string();
+        Ok(target.with_file_name(format!(".{}.ssm-{}", name, tag)))
+    }
+
+    /// Grava os arquivos de `folder` na pasta de staging ao lado de `target`.
+    pub fn stage(&mut self, metadata: &SnapshotMetadata, folder: &str, target: &Path) -> Result<usize, String> {
+        // Várias origens podem ir para o mesmo destino (arquivos casados por curinga)
+        let n = self.staged.len();
+        let staging = Self::sibling(target, &format!("staging-{}", n))?;
+        let rollback = Self::sibling(target, &format!("rollback-{}", n))?;
+        if staging.exists() {
+            fs::remove_dir_all(&staging).map_err(|e| e.to_string())?;
+        }
+        fs::create_dir_all(&staging).map_err(|e| e.to_string())?;
+
+        match SnapshotStore::materialize(&self.backups_root, metadata, folder, &staging) {
+            Ok(count) => {
+                self.staged.push(StagedTarget {
+                    folder: folder.to_string(),
+                    target: target.to_path_buf(),
+                    staging,
+                    rollback,
+                });
+                Ok(count)
+            }
+            Err(e) => {
+                let _ = fs::remove_dir_all(&staging);
+                Err(e)
+            }
+        }
+    }
+
+    /// Descarta tudo o que foi preparado sem tocar nos destinos.
+    pub fn abort(self) {
+        for staged in &self.staged {
+            let _ = fs::remove_dir_all(&staged.staging);
+        }
+    }
+
+    pub fn commit(mut self) -> Result<(), RestoreTargetError> {
+        let staged = std::mem::take(&mut self.staged);
+        for target in &staged {
+            if let Err(error) = self.swap_in(target) {
+                self.rollback();
+                self.cleanup(&staged);
+                return Err(RestoreTargetError {
+                    folder: target.folder.clone(),
+                    target: target.target.to_string_lossy().to_string(),
+                    error,
+                });
+            }
+        }
+        self.cleanup(&staged);
+        Ok(())
+    }
+
+    fn swap_in(&mut self, staged: &StagedTarget) -> Result<(), String> {
+        if !staged.target.exists() {
+            fs::create_dir_all(&staged.target).map_err(|e| e.to_string())?;
+            self.created.push(staged.target.clone());
+        }
+
+        let files = SnapshotStore::relative_files(&staged.staging).map_err(|e| e.to_string())?;
+
+        for rel in files {
+            let live = staged.target.join(&rel);
+            let saved = if live.exists() {
+                let saved = staged.rollback.join(&rel);
+                if let Some(parent) = saved.parent() {
+                    fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                fs::rename(&live, &saved).map_err(|e| format!("{}: {}", live.to_string_lossy(), e))?;
+                Some(saved)
+            } else {
+                if let Some(parent) = live.parent() {
+                    fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                None
+            };
+            // Registrado antes do rename: se ele falhar, o original ainda é devolvido
+            self.journal.push(JournalEntry { live: live.clone(), saved });
+            fs::rename(staged.staging.join(&rel), &live)
+                .map_err(|e| format!("{}: {}", live.to_string_lossy(), e))?;
+        }
+        Ok(())
+    }
+
+    fn rollback(&mut self) {
+        for entry in self.journal.drain(..).rev() {
+            let _ = fs::remove_file(&entry.live);
+            if let Some(saved) = entry.saved {
+                let _ = fs::rename(&saved, &entry.live);
+            }
+        }
+        for created in self.created.drain(..) {
+            let _ = fs::remove_dir_all(created);
+        }
+    }
+
+    fn cleanup(&self, staged: &[StagedTarget]) {
+        for target in staged {
+            let _ = fs::remove_dir_all(&target.staging);
+            let _ = fs::remove_dir_all(&target.rollback);
+        }
+    }
+}
@@ -0,0 +1,46 @@
+// src-tauri/src/services/retention_service.rs
+use std::collections::HashSet;
+use chrono::NaiveDateTime;
+use crate::models::{RetentionDecision, RetentionPolicy, RetentionReason};
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+
+pub struct RetentionService;
+
+impl RetentionService {
+    /// Decide quais snapshots a política mantém. `names` são os nomes das
+    /// pastas (timestamps); a ordem de entrada não importa.
+    pub fn apply(names: &[String], policy: &RetentionPolicy) -> Vec<RetentionDecision> {
+        let mut sorted: Vec<&String> = names.iter().collect();
+        sorted.sort_by(|a, b| b.cmp(a));
+
+        // Cada camada guarda o snapshot mais novo de cada período distinto
+        let tiers = [
+            (RetentionReason::Hourly, "%Y-%m-%d %H", policy.hourly),
+            (RetentionReason::Daily, "%Y-%m-%d", policy.daily),
+            (RetentionReason::Weekly, "%G-W%V", policy.weekly),
+        ];
+        let mut seen: Vec<HashSet<String>> = vec![HashSet::new(); tiers.len()];
+
+        sorted.into_iter().enumerate().map(|(idx, name)| {
+            let mut reasons = Vec::new();
+            if idx < policy.keep_last {
+                reasons.push(RetentionReason::Last);
+            }
+            if let Some(time) = Self::parse_timestamp(name) {
+                for ((reason, format, count), periods) in tiers.iter().zip(seen.iter_mut()) {
+                    if periods.len() < *count && periods.insert(time.format(format).to_string()) {
+                        reasons.push(*reason);
+                    }
+                }
+            }
+            RetentionDecision { name: name.clone(), keep: !reasons.is_empty(), reasons }
+        }).collect()
+    }
+
+    /// Lê o timestamp do nome da pasta (ignora sufixos como `_pre-restore`).
+    pub fn parse_timestamp(name: &str) -> Option<NaiveDateTime> {
+        let stamp = name.get(..19)?;
+        NaiveDateTime::parse_from_str(stamp, TIMESTAMP_FORMAT).ok()
+    }
+}
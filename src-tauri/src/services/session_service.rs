@@ -0,0 +1,211 @@
+// src-tauri/src/services/session_service.rs
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use crate::models::{AutoBackupMode, GameSession, WatchStatus};
+use crate::services::backup_service::BackupService;
+use crate::services::config_service::ConfigService;
+use crate::services::steam_service::SteamService;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+struct TrackedGame {
+    game_name: String,
+    install_dir: Option<PathBuf>,
+    running: bool,
+    /// Quando o backup pós-sessão deve rodar.
+    backup_at: Option<Instant>,
+}
+
+struct Monitor {
+    sender: Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+/// Jogos acompanhados no modo `Session`; uma única thread varre o `/proc` por todos.
+#[derive(Default)]
+pub struct SessionState {
+    games: Mutex<HashMap<u32, TrackedGame>>,
+    monitor: Mutex<Option<Monitor>>,
+}
+
+pub struct SessionService;
+
+impl SessionService {
+    pub fn track(app: &AppHandle, state: &SessionState, game_id: u32, game_name: String) -> Result<(), String> {
+        if !Path::new("/proc").is_dir() {
+            return Err("Detecção de sessão disponível apenas no Linux.".to_string());
+        }
+
+        // Sem pasta de instalação ainda dá para achar o jogo pelo `SteamAppId`
+        state.games.lock().unwrap().insert(game_id, TrackedGame {
+            game_name,
+            install_dir: SteamService::get_install_dir(game_id),
+            running: false,
+            backup_at: None,
+        });
+
+        let mut monitor = state.monitor.lock().unwrap();
+        if monitor.as_ref().is_none_or(|m| m.thread.is_finished()) {
+            *monitor = Some(Self::spawn_monitor(app.clone()));
+        }
+        Ok(())
+    }
+
+    /// Retorna `false` se o jogo não estava sendo acompanhado.
+    pub fn untrack(state: &SessionState, game_id: u32) -> bool {
+        let mut games = state.games.lock().unwrap();
+        let removed = games.remove(&game_id).is_some();
+        if games.is_empty() {
+            if let Some(monitor) = state.monitor.lock().unwrap().take() {
+                let _ = monitor.sender.send(());
+            }
+        }
+        removed
+    }
+
+    pub fn list_tracked(state: &SessionState) -> Vec<WatchStatus> {
+        let running = state.monitor.lock().unwrap().as_ref().is_some_and(|m| !m.thread.is_finished());
+        state.games.lock().unwrap().iter()
+            .map(|(game_id, game)| WatchStatus {
+                game_id: *game_id,
+                game_name: game.game_name.clone(),
+                mode: AutoBackupMode::Session,
+                paths: game.install_dir.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+                running,
+                error: None,
+            })
+            .collect()
+    }
+
+    pub fn running_games(state: &SessionState) -> Vec<GameSession> {
+        let mut sessions: Vec<GameSession> = state.games.lock().unwrap().iter()
+            .filter(|(_, game)| game.running)
+            .map(|(game_id, game)| GameSession { game_id: *game_id, game_name: game.game_name.clone() })
+            .collect();
+        sessions.sort_by(|a, b| a.game_name.cmp(&b.game_name));
+        sessions
+    }
+
+    /// Para o monitor; backups pós-sessão pendentes rodam antes de sair.
+    pub fn stop_all(state: &SessionState) {
+        let monitor = state.monitor.lock().unwrap().take();
+        if let Some(monitor) = monitor {
+            let _ = monitor.sender.send(());
+            let _ = monitor.thread.join();
+        }
+    }
+
+    fn spawn_monitor(app: AppHandle) -> Monitor {
+        let (tx, rx) = channel();
+        let thread = std::thread::spawn(move || {
+            loop {
+                match rx.recv_timeout(POLL_INTERVAL) {
+                    Err(RecvTimeoutError::Timeout) => Self::poll(&app),
+                    _ => break,
+                }
+            }
+            Self::flush(&app);
+        });
+        Monitor { sender: tx, thread }
+    }
+
+    fn poll(app: &AppHandle) {
+        let state = app.state::<SessionState>();
+        let targets: Vec<(u32, Option<PathBuf>)> = state.games.lock().unwrap().iter()
+            .map(|(game_id, game)| (*game_id, game.install_dir.clone()))
+            .collect();
+        if targets.is_empty() { return; }
+        let running = Self::scan_processes(&targets);
+
+        let mut events = Vec::new();
+        let mut due = Vec::new();
+        {
+            let mut games = state.games.lock().unwrap();
+            let now = Instant::now();
+            for (game_id, game) in games.iter_mut() {
+                let is_running = running.contains(game_id);
+                if is_running != game.running {
+                    game.running = is_running;
+                    game.backup_at = if is_running {
+                        None
+                    } else {
+                        let delay = ConfigService::load_config(app).watch_settings(*game_id).exit_delay_secs;
+                        Some(now + Duration::from_secs(delay))
+                    };
+                    let event = if is_running { "game-started" } else { "game-stopped" };
+                    events.push((event, GameSession { game_id: *game_id, game_name: game.game_name.clone() }));
+                }
+                if game.backup_at.is_some_and(|at| at <= now) {
+                    game.backup_at = None;
+                    due.push((*game_id, game.game_name.clone()));
+                }
+            }
+        }
+
+        for (event, session) in events {
+            let _ = app.emit(event, session);
+        }
+        for (game_id, game_name) in due {
+            BackupService::perform_auto_backup(app, game_id, &game_name, None);
+        }
+    }
+
+    /// Faz na hora os backups que ainda esperavam o `exit_delay_secs`.
+    fn flush(app: &AppHandle) {
+        let due: Vec<(u32, String)> = app.state::<SessionState>().games.lock().unwrap().iter_mut()
+            .filter_map(|(game_id, game)| game.backup_at.take().map(|_| (*game_id, game.game_name.clone())))
+            .collect();
+        for (game_id, game_name) in due {
+            BackupService::perform_auto_backup(app, game_id, &game_name, None);
+        }
+    }
+
+    /// Jogos com algum processo rodando: `SteamAppId` no ambiente (Steam e
+    /// Proton definem) ou executável dentro da pasta de instalação.
+    fn scan_processes(targets: &[(u32, Option<PathBuf>)]) -> HashSet<u32> {
+        let mut running = HashSet::new();
+        let entries = match fs::read_dir("/proc") {
+            Ok(e) => e,
+            Err(_) => return running,
+        };
+        let own_pid = std::process::id();
+
+        for entry in entries.flatten() {
+            let pid = match entry.file_name().to_str().and_then(|n| n.parse::<u32>().ok()) {
+                Some(pid) => pid,
+                None => continue,
+            };
+            if pid == own_pid { continue; }
+            let proc_dir = entry.path();
+
+            if let Some(app_id) = Self::steam_app_id(&proc_dir) {
+                if targets.iter().any(|(game_id, _)| *game_id == app_id) {
+                    running.insert(app_id);
+                    continue;
+                }
+            }
+            if let Ok(exe) = fs::read_link(proc_dir.join("exe")) {
+                for (game_id, install_dir) in targets {
+                    if install_dir.as_ref().is_some_and(|dir| exe.starts_with(dir)) {
+                        running.insert(*game_id);
+                    }
+                }
+            }
+        }
+        running
+    }
+
+    /// Processos de outros usuários não deixam ler o `environ`.
+    fn steam_app_id(proc_dir: &Path) -> Option<u32> {
+        let environ = fs::read(proc_dir.join("environ")).ok()?;
+        environ.split(|b| *b == 0)
+            .find_map(|var| var.strip_prefix(b"SteamAppId="))
+            .and_then(|value| std::str::from_utf8(value).ok()?.parse().ok())
+    }
+}
@@ -0,0 +1,352 @@
+// src-tauri/src/services/snapshot_store.rs
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, UNIX_EPOCH};
+use sha2::{Digest, Sha256};
+use crate::models::{SnapshotFile, SnapshotMetadata};
+use crate::services::file_filter::FileFilter;
+
+pub const STORE_DIR: &str = ".store";
+pub const METADATA_FILE: &str = "snapshot.json";
+pub const LOCK_FILE: &str = "lock";
+
+// Backups e GC não podem rodar ao mesmo tempo: um blob recém-gravado
+// só passa a ser referenciado quando o snapshot.json é escrito.
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// O mutex serializa as threads deste processo; o lock de arquivo em
+/// `.store/lock` serializa com outros processos (o modo `wrap` roda em
+/// paralelo à janela).
+pub struct StoreGuard {
+    _file: File,
+    _guard: MutexGuard<'static, ()>,
+}
+
+/// Armazenamento de blobs endereçados por SHA-256, compartilhado entre
+/// todos os snapshots e jogos de `SaveManagerBackups`.
+pub struct SnapshotStore;
+
+impl SnapshotStore {
+    pub fn lock(backups_root: &Path) -> Result<StoreGuard, String> {
+        let guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let store = backups_root.join(STORE_DIR);
+        fs::create_dir_all(&store).map_err(|e| e.to_string())?;
+        let file = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(store.join(LOCK_FILE))
+            .map_err(|e| e.to_string())?;
+        file.lock().map_err(|e| format!("Erro ao travar o store: {}", e))?;
+        Ok(StoreGuard { _file: file, _guard: guard })
+    }
+
+    fn objects_dir(backups_root: &Path) -> PathBuf {
+        backups_root.join(STORE_DIR).join("objects")
+    }
+
+    pub fn blob_path(backups_root: &Path, hash: &str) -> PathBuf {
+        Self::objects_dir(backups_root).join(&hash[..2]).join(hash)
+    }
+
+    pub fn hash_file(path: &Path) -> io::Result<String> {
+        let mut file = File::open(path)?;
+        let mut hasher = Sha256::new();
+        io::copy(&mut file, &mut hasher)?;
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    /// Grava o arquivo no store (se ainda não existir) e retorna `(hash, tamanho)`.
+    pub fn put_file(backups_root: &Path, source: &Path) -> io::Result<(String, u64)> {
+        let hash = Self::hash_file(source)?;
+        let blob = Self::blob_path(backups_root, &hash);
+        if blob.exists() {
+            return Ok((hash, fs::metadata(&blob)?.len()));
+        }
+
+        // O jogo pode alterar o arquivo entre o hash e a cópia, então o hash
+        // definitivo é calculado sobre os bytes efetivamente copiados.
+        let tmp_dir = backups_root.join(STORE_DIR).join("tmp");
+        fs::create_dir_all(&tmp_dir)?;
+        let tmp = tmp_dir.join(format!(
+            "{}-{}-{}.part",
+            hash,
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let mut input = File::open(source)?;
+        let mut output = File::create(&tmp)?;
+        let mut hasher = Sha256::new();
+        let mut buf = vec![0u8; 64 * 1024];
+        let mut size = 0u64;
+        loop {
+            let n = input.read(&mut buf)?;
+            if n == 0 { break; }
+            hasher.update(&buf[..n]);
+            output.write_all(&buf[..n])?;
+            size += n as u64;
+        }
+        output.sync_all()?;
+        drop(output);
+
+        let actual = hex::encode(hasher.finalize());
+        let blob = Self::blob_path(backups_root, &actual);
+        if blob.exists() {
+            fs::remove_file(&tmp)?;
+        } else {
+            if let Some(parent) = blob.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(&tmp, &blob)?;
+        }
+        Ok((actual, size))
+    }
+
+    /// Percorre `source` recursivamente e grava cada arquivo aceito por
+    /// `filter` no store, registrando-os sob `prefix/<caminho relativo>`.
+    pub fn put_dir(backups_root: &Path, source: &Path, prefix: &str, filter: &FileFilter) -> io::Result<Vec<SnapshotFile>> {
+        let mut files = Vec::new();
+        Self::put_dir_inner(backups_root, source, "", prefix, filter, !filter.has_includes(), &mut files)?;
+        Ok(files)
+    }
+
+    /// Como `put_dir`, mas aceita também um arquivo isolado, gravado como
+    /// `prefix/<nome do arquivo>`.
+    pub fn put_path(backups_root: &Path, source: &Path, prefix: &str, filter: &FileFilter) -> io::Result<Vec<SnapshotFile>> {
+        if source.is_dir() {
+            return Self::put_dir(backups_root, source, prefix, filter);
+        }
+
+        let name = source.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        if filter.excludes(&name, false) || (filter.has_includes() && !filter.includes(&name, false)) {
+            return Ok(Vec::new());
+        }
+        let mtime = fs::metadata(source).map(|m| Self::mtime_secs(&m)).unwrap_or(0);
+        let (sha256, size) = Self::put_file(backups_root, source)?;
+        Ok(vec![SnapshotFile { path: format!("{}/{}", prefix, name), size, mtime, sha256 }])
+    }
+
+    fn put_dir_inner(
+        backups_root: &Path,
+        dir: &Path,
+        rel_dir: &str,
+        prefix: &str,
+        filter: &FileFilter,
+        included: bool,
+        files: &mut Vec<SnapshotFile>,
+    ) -> io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let rel = if rel_dir.is_empty() { name } else { format!("{}/{}", rel_dir, name) };
+            let is_dir = path.is_dir();
+            if filter.excludes(&rel, is_dir) { continue; }
+
+            // Uma pasta incluída inclui tudo o que está dentro dela
+            let included = included || filter.includes(&rel, is_dir);
+            if is_dir {
+                Self::put_dir_inner(backups_root, &path, &rel, prefix, filter, included, files)?;
+            } else if path.is_file() && included {
+                let mtime = fs::metadata(&path).map(|m| Self::mtime_secs(&m)).unwrap_or(0);
+                let (sha256, size) = Self::put_file(backups_root, &path)?;
+                files.push(SnapshotFile { path: format!("{}/{}", prefix, rel), size, mtime, sha256 });
+            }
+        }
+        Ok(())
+    }
+
+    /// Todos os arquivos sob `dir`, como caminhos relativos a ele.
+    pub fn relative_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        Self::relative_files_inner(dir, Path::new(""), &mut files)?;
+        Ok(files)
+    }
+
+    fn relative_files_inner(dir: &Path, rel: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let child = rel.join(entry.file_name());
+            if path.is_dir() {
+                Self::relative_files_inner(&path, &child, files)?;
+            } else if path.is_file() {
+                files.push(child);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn mtime_secs(metadata: &fs::Metadata) -> i64 {
+        metadata.modified().ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0)
+    }
+
+    pub fn read_metadata(snapshot_dir: &Path) -> Option<SnapshotMetadata> {
+        let content = fs::read_to_string(snapshot_dir.join(METADATA_FILE)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn write_metadata(snapshot_dir: &Path, metadata: &SnapshotMetadata) -> Result<(), String> {
+        fs::create_dir_all(snapshot_dir).map_err(|e| e.to_string())?;
+        let content = serde_json::to_string_pretty(metadata).map_err(|e| e.to_string())?;
+        let tmp = snapshot_dir.join(format!("{}.tmp", METADATA_FILE));
+        fs::write(&tmp, content).map_err(|e| e.to_string())?;
+        fs::rename(&tmp, snapshot_dir.join(METADATA_FILE)).map_err(|e| e.to_string())
+    }
+
+    /// Lê o `snapshot.json`; snapshots antigos (cópia completa da pasta) são
+    /// importados para o store na primeira leitura.
+    pub fn load_or_import(backups_root: &Path, snapshot_dir: &Path) -> Result<SnapshotMetadata, String> {
+        if snapshot_dir.join(METADATA_FILE).exists() {
+            return Self::read_metadata(snapshot_dir).ok_or("snapshot.json inválido".to_string());
+        }
+
+        let _guard = Self::lock(backups_root)?;
+        // Outra leitura pode ter importado enquanto esperávamos o lock
+        if snapshot_dir.join(METADATA_FILE).exists() {
+            return Self::read_metadata(snapshot_dir).ok_or("snapshot.json inválido".to_string());
+        }
+        let mut metadata = SnapshotMetadata::default();
+        let mut groups = Vec::new();
+        for entry in fs::read_dir(snapshot_dir).map_err(|e| e.to_string())?.flatten() {
+            let path = entry.path();
+            if !path.is_dir() { continue; }
+            let group = entry.file_name().to_string_lossy().to_string();
+
+            // O fs_extra copiava a pasta de origem para dentro do grupo
+            // (ex.: Custom_Saves/<pasta>/...), então esse nível extra é descartado.
+            let children: Vec<_> = fs::read_dir(&path).map_err(|e| e.to_string())?.flatten().collect();
+            let source = if children.len() == 1 && children[0].path().is_dir() {
+                children[0].path()
+            } else {
+                path.clone()
+            };
+
+            let files = Self::put_dir(backups_root, &source, &group, &FileFilter::default()).map_err(|e| e.to_string())?;
+            Self::merge_files(&mut metadata.files, files);
+            groups.push(path);
+        }
+
+        Self::write_metadata(snapshot_dir, &metadata)?;
+        for group in groups {
+            let _ = fs::remove_dir_all(group);
+        }
+        Ok(metadata)
+    }
+
+    /// Adiciona `new` a `files`, substituindo entradas com o mesmo caminho.
+    pub fn merge_files(files: &mut Vec<SnapshotFile>, new: Vec<SnapshotFile>) {
+        let new_paths: HashSet<&str> = new.iter().map(|f| f.path.as_str()).collect();
+        files.retain(|f| !new_paths.contains(f.path.as_str()));
+        files.extend(new);
+    }
+
+    /// Recria em `dest` os arquivos do snapshot que estão sob `prefix`.
+    pub fn materialize(backups_root: &Path, metadata: &SnapshotMetadata, prefix: &str, dest: &Path) -> Result<usize, String> {
+        let group = format!("{}/", prefix);
+        let mut restored = 0;
+        for file in metadata.files.iter().filter(|f| f.path.starts_with(&group)) {
+            let target = dest.join(&file.path[group.len()..]);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            fs::copy(Self::blob_path(backups_root, &file.sha256), &target)
+                .map_err(|e| format!("{}: {}", file.path, e))?;
+            Self::set_mtime(&target, file.mtime);
+            restored += 1;
+        }
+        Ok(restored)
+    }
+
+    fn set_mtime(path: &Path, mtime: i64) {
+        if mtime <= 0 { return; }
+        if let Ok(f) = File::options().write(true).open(path) {
+            let _ = f.set_modified(UNIX_EPOCH + Duration::from_secs(mtime as u64));
+        }
+    }
+
+    /// Confere se o blob existe e se o conteúdo ainda bate com o hash.
+    /// Retorna `None` quando o blob não existe.
+    pub fn verify_blob(backups_root: &Path, hash: &str) -> Option<bool> {
+        let blob = Self::blob_path(backups_root, hash);
+        if !blob.is_file() { return None; }
+        Some(Self::hash_file(&blob).map(|actual| actual == hash).unwrap_or(false))
+    }
+
+    pub fn has_group(metadata: &SnapshotMetadata, prefix: &str) -> bool {
+        let group = format!("{}/", prefix);
+        metadata.files.iter().any(|f| f.path.starts_with(&group))
+    }
+
+    /// Remove os blobs que não são referenciados por nenhum snapshot de nenhum jogo.
+    /// Retorna `(blobs removidos, bytes liberados)`.
+    pub fn collect_garbage(backups_root: &Path) -> Result<(usize, u64), String> {
+        let _guard = Self::lock(backups_root)?;
+        let referenced = Self::referenced_hashes(backups_root)?;
+
+        let mut removed = 0;
+        let mut freed = 0;
+        let objects = Self::objects_dir(backups_root);
+        if !objects.exists() { return Ok((0, 0)); }
+
+        for bucket in fs::read_dir(&objects).map_err(|e| e.to_string())?.flatten() {
+            if !bucket.path().is_dir() { continue; }
+            for blob in fs::read_dir(bucket.path()).map_err(|e| e.to_string())?.flatten() {
+                let hash = blob.file_name().to_string_lossy().to_string();
+                if referenced.contains(&hash) { continue; }
+                let size = blob.metadata().map(|m| m.len()).unwrap_or(0);
+                if fs::remove_file(blob.path()).is_ok() {
+                    removed += 1;
+                    freed += size;
+                }
+            }
+            let _ = fs::remove_dir(bucket.path());
+        }
+        Ok((removed, freed))
+    }
+
+    /// Um `snapshot.json` ilegível (corrompido ou de uma versão mais nova)
+    /// aborta o GC: sem ele não dá para saber quais blobs ainda são usados.
+    fn referenced_hashes(backups_root: &Path) -> Result<HashSet<String>, String> {
+        let mut hashes = HashSet::new();
+        for game_dir in Self::game_dirs(backups_root) {
+            for snapshot_dir in Self::snapshot_dirs(&game_dir) {
+                if !snapshot_dir.join(METADATA_FILE).exists() { continue; }
+                match Self::read_metadata(&snapshot_dir) {
+                    Some(metadata) => hashes.extend(metadata.files.into_iter().map(|f| f.sha256)),
+                    None => return Err(format!("snapshot.json inválido em {}; limpeza cancelada.", snapshot_dir.display())),
+                }
+            }
+        }
+        Ok(hashes)
+    }
+
+    /// Pastas de jogos dentro de `SaveManagerBackups` (ignora o próprio store).
+    pub fn game_dirs(backups_root: &Path) -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = fs::read_dir(backups_root)
+            .map(|entries| entries.flatten().map(|e| e.path()).collect())
+            .unwrap_or_default();
+        dirs.retain(|p| p.is_dir() && p.file_name().is_some_and(|n| n != STORE_DIR));
+        dirs.sort();
+        dirs
+    }
+
+    /// Snapshots de um jogo, do mais antigo para o mais recente.
+    pub fn snapshot_dirs(game_dir: &Path) -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = fs::read_dir(game_dir)
+            .map(|entries| entries.flatten().map(|e| e.path()).collect())
+            .unwrap_or_default();
+        dirs.retain(|p| p.is_dir());
+        dirs.sort();
+        dirs
+    }
+}
@@ -1,15 +1,21 @@
-use std::path::{PathBuf};
+use std::path::{Path, PathBuf};
 use std::fs;
 use std::collections::HashMap;
 use steamlocate::SteamDir;
 use directories::{BaseDirs, UserDirs};
 use tauri::{AppHandle, Manager};
-use crate::models::{GameInfo, LudusaviManifest, CustomGameEntry};
+use crate::models::{GameInfo, LudusaviFile, LudusaviManifest, CustomGameEntry, SteamAccount};
+use crate::services::backup_service::BackupService;
+use crate::services::config_service::ConfigService;
+use crate::services::placeholders::{expand_placeholders, PlaceholderContext};
+
+/// Diferença entre um SteamID64 e o SteamID3 usado nas pastas de `userdata`.
+const STEAM_ID64_BASE: u64 = 76561197960265728;
 
 pub struct SteamService;
 
 impl SteamService {
-    pub fn list_installed_games() -> Vec<GameInfo> {
+    pub fn list_installed_games(app: &AppHandle) -> Vec<GameInfo> {
         let steamdir = match SteamDir::locate() {
             Ok(d) => d,
             Err(_) => return Vec::new(),
@@ -18,16 +24,16 @@ impl SteamService {
         let mut games_list = Vec::new();
         if let Ok(libraries) = steamdir.libraries() {
             for library in libraries.flatten() {
-                for app in library.apps().flatten() {
-                    let name = app.name.clone().unwrap_or("Unknown".to_string());
+                for steam_app in library.apps().flatten() {
+                    let name = steam_app.name.clone().unwrap_or("Unknown".to_string());
                     if name == "Steamworks Common Redistributables" || name.contains("Proton") {
                         continue;
                     }
                     games_list.push(GameInfo {
-                        id: app.app_id,
+                        id: steam_app.app_id,
                         name: name.clone(),
-                        install_dir: app.install_dir.to_string(),
-                        last_backup: Self::check_existing_backup(&name),
+                        install_dir: steam_app.install_dir.to_string(),
+                        last_backup: Self::check_existing_backup(app, steam_app.app_id),
                     });
                 }
             }
@@ -35,11 +41,8 @@ impl SteamService {
         games_list
     }
 
-    pub fn check_existing_backup(game_name: &str) -> Option<String> {
-        let user_dirs = UserDirs::new()?;
-        let doc_dir = user_dirs.document_dir()?;
-        let safe_name = game_name.replace(|c: char| !c.is_alphanumeric() && c != ' ', "_");
-        let backup_path = doc_dir.join("SaveManagerBackups").join(safe_name);
+    pub fn check_existing_backup(app: &AppHandle, game_id: u32) -> Option<String> {
+        let backup_path = BackupService::game_dir(app, game_id);
         
         if !backup_path.exists() { return None; }
         
@@ -54,108 +57,406 @@ impl SteamService {
         entries.pop()
     }
 
-    pub fn resolve_path_root(path_str: &str, game_id: u32) -> PathBuf {
+    /// Valores das variáveis Ludusavi para o jogo: biblioteca e pasta de
+    /// instalação via steamlocate, contas de `userdata` e, no Linux, as
+    /// pastas Windows dentro do prefixo do Proton.
+    pub fn placeholder_context(game_id: u32) -> PlaceholderContext {
         let base_dirs = BaseDirs::new().expect("BaseDirs error");
         let user_dirs = UserDirs::new().expect("UserDirs error");
+        let to_string = |p: &Path| p.to_string_lossy().to_string();
+        let home = user_dirs.home_dir().to_path_buf();
 
-        // Lógica para Linux/Proton
-        if cfg!(target_os = "linux") && (path_str.contains("AppData") || path_str.contains("Saved Games")) {
-            if let Ok(steamdir) = SteamDir::locate() {
-                let proton_path = steamdir.path()
-                    .join("steamapps")
-                    .join("compatdata")
-                    .join(game_id.to_string())
-                    .join("pfx")
-                    .join("drive_c")
-                    .join("users")
-                    .join("steamuser");
-
-                if proton_path.exists() {
-                    let mut resolved = path_str.to_string();
-                    resolved = resolved.replace("%USERPROFILE%", proton_path.to_str().unwrap());
-                    resolved = resolved.replace("<home>", proton_path.to_str().unwrap());
-                    resolved = resolved.replace("%APPDATA%", proton_path.join("AppData").join("Roaming").to_str().unwrap());
-                    resolved = resolved.replace("%LOCALAPPDATA%", proton_path.join("AppData").join("Local").to_str().unwrap());
-
-                    let mut p = PathBuf::from(resolved.replace("\\", "/"));
-                    if p.to_string_lossy().starts_with("C:") || p.to_string_lossy().starts_with("c:") {
-                        p = PathBuf::from(p.to_string_lossy()[2..].to_string());
-                    }
-                    return p;
-                }
+        let mut ctx = PlaceholderContext {
+            home: Some(to_string(&home)),
+            win_home: Some(to_string(&home)),
+            store_game_id: Some(game_id.to_string()),
+            os_user_name: std::env::var("USER").or_else(|_| std::env::var("USERNAME")).ok()
+                .or_else(|| home.file_name().map(|n| n.to_string_lossy().to_string())),
+            ..Default::default()
+        };
+
+        if let Ok(steamdir) = SteamDir::locate() {
+            if let Ok(Some((steam_app, library))) = steamdir.find_app(game_id) {
+                ctx.root = Some(to_string(library.path()));
+                ctx.game = Some(steam_app.install_dir.clone());
+                ctx.base = Some(to_string(&library.resolve_app_dir(&steam_app)));
+            }
+            if let Ok(entries) = fs::read_dir(steamdir.path().join("userdata")) {
+                ctx.store_user_ids = entries.flatten()
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .filter(|name| name.parse::<u32>().is_ok_and(|id| id != 0))
+                    .collect();
+                ctx.store_user_ids.sort();
             }
         }
 
-        // Mapeamento de variáveis de ambiente
-        let mut replacements = HashMap::new();
-        replacements.insert("%LOCALAPPDATA%", base_dirs.data_local_dir().to_str().unwrap());
-        replacements.insert("%APPDATA%", base_dirs.config_dir().to_str().unwrap());
-        replacements.insert("%USERPROFILE%", user_dirs.home_dir().to_str().unwrap());
-        replacements.insert("<home>", user_dirs.home_dir().to_str().unwrap());
-        replacements.insert("<winAppData>", base_dirs.config_dir().to_str().unwrap());
-        replacements.insert("<winLocalAppData>", base_dirs.data_local_dir().to_str().unwrap());
-
-        if let Some(doc) = user_dirs.document_dir() {
-            replacements.insert("%DOCUMENTS%", doc.to_str().unwrap());
-            replacements.insert("<winDocuments>", doc.to_str().unwrap());
+        if cfg!(target_os = "windows") {
+            let env_or = |var: &str, default: &str| std::env::var(var).unwrap_or_else(|_| default.to_string());
+            ctx.win_app_data = Some(to_string(base_dirs.config_dir()));
+            ctx.win_local_app_data = Some(to_string(base_dirs.data_local_dir()));
+            ctx.win_local_app_data_low = Some(to_string(&home.join("AppData").join("LocalLow")));
+            ctx.win_documents = user_dirs.document_dir().map(to_string);
+            ctx.win_public = Some(env_or("PUBLIC", "C:/Users/Public"));
+            ctx.win_program_data = Some(env_or("PROGRAMDATA", "C:/ProgramData"));
+            ctx.win_dir = Some(env_or("WINDIR", "C:/Windows"));
+        } else if cfg!(target_os = "linux") {
+            ctx.xdg_data = Some(to_string(base_dirs.data_dir()));
+            ctx.xdg_config = Some(to_string(base_dirs.config_dir()));
+
+            // Lógica para Linux/Proton
+            if let Some(drive_c) = Self::proton_drive_c(game_id) {
+                let user = drive_c.join("users").join("steamuser");
+                let documents = if user.join("My Documents").exists() { "My Documents" } else { "Documents" };
+                ctx.win_home = Some(to_string(&user));
+                ctx.win_app_data = Some(to_string(&user.join("AppData").join("Roaming")));
+                ctx.win_local_app_data = Some(to_string(&user.join("AppData").join("Local")));
+                ctx.win_local_app_data_low = Some(to_string(&user.join("AppData").join("LocalLow")));
+                ctx.win_documents = Some(to_string(&user.join(documents)));
+                ctx.win_public = Some(to_string(&drive_c.join("users").join("Public")));
+                ctx.win_program_data = Some(to_string(&drive_c.join("ProgramData")));
+                ctx.win_dir = Some(to_string(&drive_c.join("windows")));
+                ctx.win_drive = Some(to_string(&drive_c));
+            }
         }
+        ctx
+    }
 
-        let mut resolved = path_str.to_string();
-        for (key, val) in replacements {
-            resolved = resolved.replace(key, val);
+    /// Sistema visto pelo jogo: no Linux, jogos com prefixo do Proton rodam como Windows.
+    pub fn game_os(game_id: u32) -> &'static str {
+        if cfg!(target_os = "windows") {
+            "windows"
+        } else if cfg!(target_os = "macos") {
+            "mac"
+        } else if Self::proton_drive_c(game_id).is_some() {
+            "windows"
+        } else {
+            "linux"
         }
+    }
 
-        let stop_chars = ['<', '*', '?'];
-        if let Some(idx) = resolved.find(|c| stop_chars.contains(&c)) {
-            resolved = resolved[..idx].to_string();
+    /// O prefixo fica em `compatdata` na mesma biblioteca do jogo (que pode
+    /// estar num cartão SD ou outro disco); se não estiver lá, procura em
+    /// todas as bibliotecas.
+    pub fn proton_drive_c(game_id: u32) -> Option<PathBuf> {
+        let steamdir = SteamDir::locate().ok()?;
+        let drive_c_in = |library_path: &Path| {
+            let drive_c = library_path
+                .join("steamapps")
+                .join("compatdata")
+                .join(game_id.to_string())
+                .join("pfx")
+                .join("drive_c");
+            if drive_c.exists() { Some(drive_c) } else { None }
+        };
+
+        if let Ok(Some((_app, library))) = steamdir.find_app(game_id) {
+            if let Some(drive_c) = drive_c_in(library.path()) {
+                return Some(drive_c);
+            }
         }
 
-        let p = PathBuf::from(if cfg!(target_os = "windows") {
-            resolved.replace("/", "\\")
-        } else {
-            resolved.replace("\\", "/")
-        });
-        
-        PathBuf::from(p.to_string_lossy().trim_end_matches(|c| c == '/' || c == '\\'))
+        steamdir.libraries().ok()?
+            .flatten()
+            .find_map(|library| drive_c_in(library.path()))
+            .or_else(|| drive_c_in(steamdir.path()))
+    }
+
+    /// Substitui as variáveis do caminho, preservando curingas (`*`, `?`).
+    /// Caminhos com `<storeUserId>` geram um resultado por conta.
+    pub fn resolve_paths(path_str: &str, ctx: &PlaceholderContext) -> Vec<PathBuf> {
+        expand_placeholders(path_str, ctx).into_iter().map(|resolved| {
+            let p = if cfg!(target_os = "windows") { resolved.replace("/", "\\") } else { resolved };
+            PathBuf::from(p.trim_end_matches(['/', '\\']))
+        }).collect()
+    }
+
+    pub fn resolve_path_root(path_str: &str, game_id: u32) -> PathBuf {
+        Self::path_root(path_str, &Self::placeholder_context(game_id))
+    }
+
+    /// Parte fixa do caminho: tudo antes do primeiro componente com curinga
+    /// da chave ou variável não resolvida. As contas viram curinga aqui, então
+    /// a raiz é a mesma para todas.
+    pub fn path_root(path_str: &str, ctx: &PlaceholderContext) -> PathBuf {
+        let generic = PlaceholderContext { store_user_ids: Vec::new(), ..ctx.clone() };
+        let resolved = Self::resolve_paths(path_str, &Self::glob_context(&generic))
+            .pop()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let (literal, stopped) = Self::literal_prefix(&resolved);
+        if !stopped { return PathBuf::from(literal); }
+        let cut = literal.rfind(['/', '\\']).unwrap_or(0);
+        PathBuf::from(&literal[..cut])
+    }
+
+    /// Valores das variáveis com `*`, `?` e `[` escapados: só os curingas
+    /// escritos na chave do manifesto contam como curinga.
+    fn glob_context(ctx: &PlaceholderContext) -> PlaceholderContext {
+        ctx.map_values(glob::Pattern::escape)
+    }
+
+    /// Texto literal de um padrão glob (desfazendo os escapes `[x]`) até o
+    /// primeiro curinga ou variável não resolvida. Retorna se parou antes do fim.
+    fn literal_prefix(pattern: &str) -> (String, bool) {
+        let chars: Vec<char> = pattern.chars().collect();
+        let mut literal = String::new();
+        let mut idx = 0;
+        while idx < chars.len() {
+            match chars[idx] {
+                '[' if idx + 2 < chars.len() && chars[idx + 2] == ']' => {
+                    literal.push(chars[idx + 1]);
+                    idx += 3;
+                }
+                '<' | '*' | '?' | '[' => return (literal, true),
+                c => {
+                    literal.push(c);
+                    idx += 1;
+                }
+            }
+        }
+        (literal, false)
+    }
+
+    /// Arquivos e pastas existentes que casam com a chave do manifesto.
+    pub fn expand_manifest_path(path_str: &str, ctx: &PlaceholderContext) -> Vec<PathBuf> {
+        let mut matches = Vec::new();
+        for resolved in Self::resolve_paths(path_str, &Self::glob_context(ctx)) {
+            let resolved = resolved.to_string_lossy().to_string();
+            // Variável não resolvida: usa só a parte fixa do caminho
+            if resolved.contains('<') {
+                let root = Self::path_root(path_str, ctx);
+                if root.exists() && !root.as_os_str().is_empty() { matches.push(root); }
+                continue;
+            }
+            if let Ok(paths) = glob::glob(&resolved) {
+                matches.extend(paths.flatten());
+            }
+        }
+
+        // Um curinga pode casar com uma pasta e com arquivos dentro dela
+        matches.sort();
+        let mut unique: Vec<PathBuf> = Vec::new();
+        for path in matches {
+            if !unique.iter().any(|parent| path.starts_with(parent)) {
+                unique.push(path);
+            }
+        }
+        unique
     }
 
     pub fn get_manifest_paths(app: &AppHandle, game_id: u32) -> Vec<PathBuf> {
-        let mut found_paths = Vec::new();
+        Self::get_manifest_entries(app, game_id).into_iter().map(|(_, path)| path).collect()
+    }
+
+    /// Caminhos do manifesto Ludusavi como `(chave original, caminho resolvido)`,
+    /// apenas os que valem para o sistema em que o jogo roda.
+    pub fn get_manifest_entries(app: &AppHandle, game_id: u32) -> Vec<(String, PathBuf)> {
+        let include_config = ConfigService::load_config(app).include_config_files;
+        let os = Self::game_os(game_id);
+        let keys = Self::manifest_files(app, game_id).into_iter()
+            .filter(|(_, file)| {
+                let file = file.as_ref();
+                file.is_none_or(|f| f.applies_to(os, "steam"))
+                    && (include_config || !file.is_some_and(|f| f.is_config_only()))
+            })
+            .map(|(key, _)| key)
+            .collect();
+        Self::resolve_manifest_keys(keys, game_id)
+    }
+
+    /// Todas as chaves, sem filtro de `when` e tags: snapshots antigos sem
+    /// `sources` numeravam as pastas `Game_Data_<n>` por esta lista.
+    pub fn get_legacy_manifest_entries(app: &AppHandle, game_id: u32) -> Vec<(String, PathBuf)> {
+        Self::resolve_manifest_keys(Self::manifest_files(app, game_id).into_keys().collect(), game_id)
+    }
+
+    fn resolve_manifest_keys(mut keys: Vec<String>, game_id: u32) -> Vec<(String, PathBuf)> {
+        if keys.is_empty() { return Vec::new(); }
+        keys.sort();
+        let ctx = Self::placeholder_context(game_id);
+        keys.into_iter()
+            .map(|key| {
+                let resolved = Self::path_root(&key, &ctx);
+                (key, resolved)
+            })
+            .collect()
+    }
+
+    fn manifest_files(app: &AppHandle, game_id: u32) -> HashMap<String, Option<LudusaviFile>> {
         if let Ok(app_dir) = app.path().app_data_dir() {
             let local_path = app_dir.join("manifest.yaml");
             if local_path.exists() {
                 if let Ok(content) = fs::read_to_string(local_path) {
                     if let Ok(manifest) = serde_yaml::from_str::<LudusaviManifest>(&content) {
                         for (_name, data) in manifest.games {
-                            if let Some(steam) = data.steam {
-                                if steam.id == game_id {
-                                    if let Some(files_map) = data.files {
-                                        let mut keys: Vec<String> = files_map.keys().cloned().collect();
-                                        keys.sort();
-                                        for path_key in keys {
-                                            found_paths.push(Self::resolve_path_root(&path_key, game_id));
-                                        }
-                                    }
-                                    break;
-                                }
+                            if data.steam.as_ref().is_some_and(|steam| steam.id == game_id) {
+                                return data.files.unwrap_or_default();
                             }
                         }
                     }
                 }
             }
         }
-        found_paths
+        HashMap::new()
+    }
+
+    /// Pastas `userdata/<conta>/<appid>` já existentes para o jogo, com o SteamID3 da conta.
+    pub fn get_userdata_app_dirs(game_id: u32) -> Vec<(u32, PathBuf)> {
+        let mut dirs = Vec::new();
+        if let Ok(steamdir) = SteamDir::locate() {
+            if let Ok(entries) = fs::read_dir(steamdir.path().join("userdata")) {
+                for entry in entries.flatten() {
+                    let account = match entry.file_name().to_string_lossy().parse::<u32>() {
+                        Ok(id) if id != 0 => id,
+                        _ => continue,
+                    };
+                    let possible = entry.path().join(game_id.to_string());
+                    if possible.exists() {
+                        dirs.push((account, possible));
+                    }
+                }
+            }
+        }
+        dirs
+    }
+
+    pub fn userdata_app_dir(account_id: u32, game_id: u32) -> Option<PathBuf> {
+        let steamdir = SteamDir::locate().ok()?;
+        Some(steamdir.path().join("userdata").join(account_id.to_string()).join(game_id.to_string()))
+    }
+
+    /// Contas conhecidas por esta instalação da Steam, com os nomes de `config/loginusers.vdf`.
+    pub fn get_steam_accounts() -> Vec<SteamAccount> {
+        let steamdir = match SteamDir::locate() {
+            Ok(d) => d,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut accounts = fs::read_to_string(steamdir.path().join("config").join("loginusers.vdf"))
+            .map(|content| Self::parse_login_users(&content))
+            .unwrap_or_default();
+
+        // Contas que já não aparecem no loginusers.vdf ainda podem ter saves em userdata
+        if let Ok(entries) = fs::read_dir(steamdir.path().join("userdata")) {
+            for entry in entries.flatten() {
+                if let Ok(id3) = entry.file_name().to_string_lossy().parse::<u32>() {
+                    if id3 != 0 && !accounts.iter().any(|a| a.id3 == id3) {
+                        accounts.push(SteamAccount {
+                            id3,
+                            id64: id3 as u64 + STEAM_ID64_BASE,
+                            account_name: None,
+                            persona_name: None,
+                            most_recent: false,
+                        });
+                    }
+                }
+            }
+        }
+        accounts
+    }
+
+    fn parse_login_users(content: &str) -> Vec<SteamAccount> {
+        enum Token { Str(String), Open, Close }
+
+        let mut tokens = Vec::new();
+        let mut chars = content.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => {
+                    let mut value = String::new();
+                    while let Some(c) = chars.next() {
+                        match c {
+                            '\\' => { if let Some(escaped) = chars.next() { value.push(escaped); } }
+                            '"' => break,
+                            _ => value.push(c),
+                        }
+                    }
+                    tokens.push(Token::Str(value));
+                }
+                '{' => tokens.push(Token::Open),
+                '}' => tokens.push(Token::Close),
+                _ => {}
+            }
+        }
+
+        // "users" { "<SteamID64>" { "AccountName" "..." "PersonaName" "..." } }
+        let mut accounts = Vec::new();
+        let mut depth = 0usize;
+        let mut key: Option<String> = None;
+        let mut current: Option<SteamAccount> = None;
+        for token in tokens {
+            match token {
+                Token::Open => {
+                    depth += 1;
+                    let opened = key.take();
+                    if depth == 2 {
+                        current = opened.and_then(|k| k.parse::<u64>().ok())
+                            .filter(|id64| *id64 > STEAM_ID64_BASE)
+                            .map(|id64| SteamAccount {
+                                id3: (id64 - STEAM_ID64_BASE) as u32,
+                                id64,
+                                account_name: None,
+                                persona_name: None,
+                                most_recent: false,
+                            });
+                    }
+                }
+                Token::Close => {
+                    if depth == 2 {
+                        if let Some(account) = current.take() {
+                            accounts.push(account);
+                        }
+                    }
+                    depth = depth.saturating_sub(1);
+                    key = None;
+                }
+                Token::Str(value) => match key.take() {
+                    None => key = Some(value),
+                    Some(k) => {
+                        if let (2, Some(account)) = (depth, current.as_mut()) {
+                            match k.as_str() {
+                                "AccountName" => account.account_name = Some(value),
+                                "PersonaName" => account.persona_name = Some(value),
+                                "MostRecent" => account.most_recent = value == "1",
+                                _ => {}
+                            }
+                        }
+                    }
+                },
+            }
+        }
+        accounts
+    }
+
+    pub fn get_app_build_id(game_id: u32) -> Option<u64> {
+        let steamdir = SteamDir::locate().ok()?;
+        let (app, _library) = steamdir.find_app(game_id).ok()??;
+        app.build_id
+    }
+
+    pub fn get_game_name(game_id: u32) -> Option<String> {
+        let steamdir = SteamDir::locate().ok()?;
+        let (app, _library) = steamdir.find_app(game_id).ok()??;
+        app.name
+    }
+
+    pub fn get_install_dir(game_id: u32) -> Option<PathBuf> {
+        let steamdir = SteamDir::locate().ok()?;
+        let (app, library) = steamdir.find_app(game_id).ok()??;
+        Some(library.resolve_app_dir(&app))
     }
 
     pub fn get_custom_path(app: &AppHandle, game_id: u32) -> Option<PathBuf> {
+        Self::get_custom_entry(app, game_id).map(|entry| Self::resolve_path_root(&entry.win, game_id))
+    }
+
+    pub fn get_custom_entry(app: &AppHandle, game_id: u32) -> Option<CustomGameEntry> {
         if let Ok(app_dir) = app.path().app_data_dir() {
             let custom_path = app_dir.join("custom_manifest.json");
             if custom_path.exists() {
                 if let Ok(content) = fs::read_to_string(custom_path) {
-                    if let Ok(json) = serde_json::from_str::<HashMap<String, CustomGameEntry>>(&content) {
-                        if let Some(entry) = json.get(&game_id.to_string()) {
-                            return Some(Self::resolve_path_root(&entry.win, game_id));
-                        }
+                    if let Ok(mut json) = serde_json::from_str::<HashMap<String, CustomGameEntry>>(&content) {
+                        return json.remove(&game_id.to_string());
                     }
                 }
             }
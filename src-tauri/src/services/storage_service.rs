@@ -0,0 +1,200 @@
+// src-tauri/src/services/storage_service.rs
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::models::{GameStorage, SnapshotKind, StorageReport};
+use crate::services::snapshot_store::{SnapshotStore, STORE_DIR};
+
+const MB: u64 = 1024 * 1024;
+
+/// Limites de espaço em MB; `None` desativa o limite.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StorageQuota {
+    pub per_game_mb: Option<u64>,
+    pub total_mb: Option<u64>,
+}
+
+struct SnapshotUsage {
+    game: String,
+    game_name: String,
+    dir: PathBuf,
+    name: String,
+    /// O snapshot mais recente de cada jogo nunca é removido pela cota.
+    protected: bool,
+    blobs: HashMap<String, u64>,
+}
+
+/// Contagem de referências por blob: um blob só libera espaço quando o
+/// último snapshot que o usa é removido.
+#[derive(Default)]
+struct RefCounts {
+    refs: HashMap<String, usize>,
+    used: u64,
+}
+
+impl RefCounts {
+    fn add(&mut self, blobs: &HashMap<String, u64>) {
+        for (hash, size) in blobs {
+            let count = self.refs.entry(hash.clone()).or_insert(0);
+            if *count == 0 { self.used += size; }
+            *count += 1;
+        }
+    }
+
+    /// Retorna os bytes liberados.
+    fn remove(&mut self, blobs: &HashMap<String, u64>) -> u64 {
+        let mut freed = 0;
+        for (hash, size) in blobs {
+            if let Some(count) = self.refs.get_mut(hash) {
+                *count -= 1;
+                if *count == 0 {
+                    self.refs.remove(hash);
+                    freed += size;
+                }
+            }
+        }
+        self.used -= freed;
+        freed
+    }
+}
+
+/// Snapshots que precisam sair para respeitar as cotas, do mais antigo para o mais novo.
+pub struct QuotaPlan {
+    pub deletions: Vec<PathBuf>,
+    /// Bytes liberados por pasta de jogo.
+    pub freed: HashMap<String, u64>,
+    usage: HashMap<String, (usize, u64)>,
+    names: HashMap<String, String>,
+    total: u64,
+}
+
+pub struct StorageService;
+
+impl StorageService {
+    pub fn plan(backups_root: &Path, quota: StorageQuota) -> QuotaPlan {
+        let snapshots = Self::scan(backups_root);
+
+        let mut global = RefCounts::default();
+        let mut per_game: HashMap<String, RefCounts> = HashMap::new();
+        for snapshot in &snapshots {
+            global.add(&snapshot.blobs);
+            per_game.entry(snapshot.game.clone()).or_default().add(&snapshot.blobs);
+        }
+
+        let mut plan = QuotaPlan {
+            deletions: Vec::new(),
+            freed: HashMap::new(),
+            usage: HashMap::new(),
+            names: HashMap::new(),
+            total: global.used,
+        };
+        for snapshot in &snapshots {
+            let entry = plan.usage.entry(snapshot.game.clone()).or_insert((0, 0));
+            entry.0 += 1;
+            entry.1 = per_game[&snapshot.game].used;
+        }
+
+        let mut deleted = vec![false; snapshots.len()];
+
+        // 1. Cota por jogo: remove os mais antigos de cada jogo
+        if let Some(limit) = quota.per_game_mb.map(|mb| mb * MB) {
+            for (idx, snapshot) in snapshots.iter().enumerate() {
+                if snapshot.protected || per_game[&snapshot.game].used <= limit { continue; }
+                Self::remove(snapshot, &mut global, &mut per_game, &mut plan);
+                deleted[idx] = true;
+            }
+        }
+
+        // 2. Cota global: remove os mais antigos entre todos os jogos
+        if let Some(limit) = quota.total_mb.map(|mb| mb * MB) {
+            let mut order: Vec<usize> = (0..snapshots.len()).collect();
+            order.sort_by(|a, b| snapshots[*a].name.cmp(&snapshots[*b].name));
+            for idx in order {
+                if global.used <= limit { break; }
+                if deleted[idx] || snapshots[idx].protected { continue; }
+                Self::remove(&snapshots[idx], &mut global, &mut per_game, &mut plan);
+                deleted[idx] = true;
+            }
+        }
+
+        for snapshot in snapshots.iter().filter(|s| !s.game_name.is_empty()) {
+            plan.names.insert(snapshot.game.clone(), snapshot.game_name.clone());
+        }
+        plan
+    }
+
+    fn remove(snapshot: &SnapshotUsage, global: &mut RefCounts, per_game: &mut HashMap<String, RefCounts>, plan: &mut QuotaPlan) {
+        if let Some(refs) = per_game.get_mut(&snapshot.game) {
+            refs.remove(&snapshot.blobs);
+        }
+        *plan.freed.entry(snapshot.game.clone()).or_insert(0) += global.remove(&snapshot.blobs);
+        plan.deletions.push(snapshot.dir.clone());
+    }
+
+    pub fn report(backups_root: &Path, quota: StorageQuota) -> StorageReport {
+        let plan = Self::plan(backups_root, quota);
+        let store_size = Self::dir_size(&backups_root.join(STORE_DIR));
+
+        let mut games: Vec<GameStorage> = plan.usage.iter().map(|(game, (snapshots, size))| GameStorage {
+            app_id: game.parse().unwrap_or(0),
+            game_name: plan.names.get(game).cloned().unwrap_or_else(|| game.clone()),
+            snapshots: *snapshots,
+            size: *size,
+            reclaimable: plan.freed.get(game).copied().unwrap_or(0),
+        }).collect();
+        games.sort_by_key(|g| std::cmp::Reverse(g.size));
+
+        let to_delete = plan.deletions.iter().map(|dir| {
+            let game = dir.parent().and_then(|p| p.file_name()).map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            let name = dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            format!("{}/{}", game, name)
+        }).collect();
+
+        // Blobs órfãos (sem snapshot) também são liberados pelo GC
+        let orphaned = store_size.saturating_sub(plan.total);
+        StorageReport {
+            games,
+            total_size: plan.total,
+            store_size,
+            per_game_quota: quota.per_game_mb.map(|mb| mb * MB),
+            total_quota: quota.total_mb.map(|mb| mb * MB),
+            reclaimable: plan.freed.values().sum::<u64>() + orphaned,
+            to_delete,
+        }
+    }
+
+    /// Snapshots com metadados, do mais antigo para o mais novo dentro de cada jogo.
+    fn scan(backups_root: &Path) -> Vec<SnapshotUsage> {
+        let mut snapshots = Vec::new();
+        for game_dir in SnapshotStore::game_dirs(backups_root) {
+            let game = game_dir.file_name().unwrap().to_string_lossy().to_string();
+            let dirs = SnapshotStore::snapshot_dirs(&game_dir);
+            let newest = dirs.iter().rev()
+                .find(|dir| SnapshotStore::read_metadata(dir).is_some_and(|m| m.kind == SnapshotKind::Backup))
+                .cloned();
+
+            for dir in dirs {
+                let metadata = match SnapshotStore::read_metadata(&dir) {
+                    Some(m) => m,
+                    None => continue,
+                };
+                let blobs = metadata.files.iter().map(|f| (f.sha256.clone(), f.size)).collect();
+                snapshots.push(SnapshotUsage {
+                    game: game.clone(),
+                    game_name: metadata.game_name,
+                    name: dir.file_name().unwrap().to_string_lossy().to_string(),
+                    protected: metadata.pinned || newest.as_ref() == Some(&dir),
+                    dir,
+                    blobs,
+                });
+            }
+        }
+        snapshots
+    }
+
+    fn dir_size(dir: &Path) -> u64 {
+        SnapshotStore::relative_files(dir).unwrap_or_default().iter()
+            .map(|rel| fs::metadata(dir.join(rel)).map(|m| m.len()).unwrap_or(0))
+            .sum()
+    }
+}
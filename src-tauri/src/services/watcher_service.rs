@@ -1,55 +1,222 @@
 // src-tauri/src/services/watcher_service.rs
-use notify::{Watcher, RecursiveMode, Config, Event};
+use notify::{Watcher, RecursiveMode, Config, Event, EventKind};
+use notify::event::ModifyKind;
+use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::mpsc::channel;
-use std::time::Duration;
-use tauri::{AppHandle, Window};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use crate::models::{AutoBackupMode, WatchSettings, WatchStatus};
 use crate::services::backup_service::BackupService;
+use crate::services::config_service::ConfigService;
+use crate::services::session_service::{SessionService, SessionState};
+use crate::services::steam_service::SteamService;
+
+enum WatchMessage {
+    Fs(notify::Result<Event>),
+    Stop,
+}
+
+pub struct WatchHandle {
+    game_name: String,
+    paths: Vec<PathBuf>,
+    sender: Sender<WatchMessage>,
+    thread: JoinHandle<()>,
+}
+
+/// Watchers ativos por app id, guardados no estado gerenciado do Tauri.
+#[derive(Default)]
+pub struct WatcherState {
+    pub watchers: Mutex<HashMap<u32, WatchHandle>>,
+    /// Jogos salvos no config cujo watcher não pôde ser iniciado.
+    pub unresolved: Mutex<HashMap<u32, WatchStatus>>,
+}
 
 pub struct WatcherService;
 
 impl WatcherService {
-    pub fn start_watching(window: Window, app: AppHandle, game_id: u32, game_name: String, paths: Vec<PathBuf>) {
-        tauri::async_runtime::spawn(async move {
-            let (tx, rx) = channel();
-
-            let mut watcher = match notify::RecommendedWatcher::new(tx, Config::default()) {
-                Ok(w) => w,
-                Err(e) => {
-                    eprintln!("Erro ao iniciar watcher: {:?}", e);
-                    return;
-                }
-            };
+    /// Pastas do manifesto e do `custom_manifest.json` monitoradas para o jogo.
+    pub fn watch_paths(app: &AppHandle, game_id: u32) -> Vec<PathBuf> {
+        let mut paths = SteamService::get_manifest_paths(app, game_id);
+        if let Some(custom) = SteamService::get_custom_path(app, game_id) {
+            paths.push(custom);
+        }
+        paths
+    }
 
-            for path in paths {
-                if path.exists() {
-                    let _ = watcher.watch(&path, RecursiveMode::Recursive);
-                }
+    /// Ativa o auto-backup no modo configurado para o jogo (arquivos ou sessão).
+    pub fn enable(app: &AppHandle, watchers: &WatcherState, sessions: &SessionState, game_id: u32, game_name: String) -> Result<(), String> {
+        Self::disable(watchers, sessions, game_id);
+        match ConfigService::load_config(app).watch_settings(game_id).mode {
+            AutoBackupMode::Files => {
+                let paths = Self::watch_paths(app, game_id);
+                if paths.is_empty() { return Err("Caminho não encontrado.".to_string()); }
+                Self::start_watching(app.clone(), watchers, game_id, game_name, paths)
+            }
+            AutoBackupMode::Session => {
+                SessionService::track(app, sessions, game_id, game_name)?;
+                watchers.unresolved.lock().unwrap().remove(&game_id);
+                Ok(())
             }
+        }
+    }
+
+    pub fn disable(watchers: &WatcherState, sessions: &SessionState, game_id: u32) -> bool {
+        let watched = Self::stop_watching(watchers, game_id);
+        SessionService::untrack(sessions, game_id) || watched
+    }
+
+    /// Reativa o auto-backup salvo em `auto_backup_games`. Retorna os jogos
+    /// que não puderam ser resolvidos; eles continuam no config.
+    pub fn restore_saved(app: &AppHandle, watchers: &WatcherState, sessions: &SessionState) -> Vec<WatchStatus> {
+        let config = ConfigService::load_config(app);
+        let mut unresolved = Vec::new();
+        for (&game_id, game_name) in &config.auto_backup_games {
+            if let Err(error) = Self::enable(app, watchers, sessions, game_id, game_name.clone()) {
+                let status = WatchStatus {
+                    game_id,
+                    game_name: game_name.clone(),
+                    mode: config.watch_settings(game_id).mode,
+                    paths: Vec::new(),
+                    running: false,
+                    error: Some(error),
+                };
+                watchers.unresolved.lock().unwrap().insert(game_id, status.clone());
+                unresolved.push(status);
+            }
+        }
+        unresolved
+    }
+
+    /// Inicia (ou reinicia, se já existir) o watcher do jogo.
+    pub fn start_watching(app: AppHandle, state: &WatcherState, game_id: u32, game_name: String, paths: Vec<PathBuf>) -> Result<(), String> {
+        Self::stop_watching(state, game_id);
+
+        let (tx, rx) = channel();
+        let fs_tx = tx.clone();
+        let mut watcher = notify::RecommendedWatcher::new(
+            move |res| { let _ = fs_tx.send(WatchMessage::Fs(res)); },
+            Config::default(),
+        ).map_err(|e| format!("Erro ao iniciar watcher: {:?}", e))?;
+
+        let watched: Vec<PathBuf> = paths.into_iter()
+            .filter(|path| path.exists() && watcher.watch(path, RecursiveMode::Recursive).is_ok())
+            .collect();
+        if watched.is_empty() { return Err("Caminho não encontrado.".to_string()); }
+
+        let name = game_name.clone();
+        let thread = std::thread::spawn(move || {
+            // O watcher vive enquanto a thread estiver rodando
+            let _watcher = watcher;
+            println!("Watcher iniciado para: {}", name);
 
-            println!("Watcher iniciado para: {}", game_name);
-
-            while let Ok(res) = rx.recv() {
-                match res {
-                    Ok(Event { kind, .. }) => {
-                        if kind.is_modify() {
-                            // Delay para estabilização da escrita do jogo
-                            tokio::time::sleep(Duration::from_secs(5)).await;
-                            
-                            let _ = BackupService::perform_backup(
-                                window.clone(), 
-                                app.clone(), 
-                                game_id, 
-                                game_name.clone()
-                            );
-                            
-                            // Na automação, mantemos o limite padrão de 10
-                            let _ = BackupService::cleanup_old_backups(game_name.clone(), 10);
+            // Rajadas de eventos viram um único backup: espera `quiet_secs` sem
+            // mudanças, mas nunca mais que `max_delay_secs` desde a primeira.
+            let mut burst: Option<(Instant, Instant)> = None;
+            let mut settings = WatchSettings::default();
+            loop {
+                let message = match burst {
+                    None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                    Some((first, last)) => {
+                        let deadline = (last + Duration::from_secs(settings.quiet_secs))
+                            .min(first + Duration::from_secs(settings.max_delay_secs));
+                        rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                    }
+                };
+
+                match message {
+                    Ok(WatchMessage::Stop) | Err(RecvTimeoutError::Disconnected) => {
+                        // Mudanças ainda no período de espera não se perdem ao parar
+                        if burst.is_some() {
+                            BackupService::perform_auto_backup(&app, game_id, &name, None);
+                        }
+                        break;
+                    }
+                    Ok(WatchMessage::Fs(Ok(event))) => {
+                        if Self::is_change(&event.kind) {
+                            let now = Instant::now();
+                            let first = match burst {
+                                Some((first, _)) => first,
+                                None => {
+                                    settings = ConfigService::load_config(&app).watch_settings(game_id);
+                                    now
+                                }
+                            };
+                            burst = Some((first, now));
                         }
                     }
-                    Err(e) => println!("Erro no watcher: {:?}", e),
+                    Ok(WatchMessage::Fs(Err(e))) => println!("Erro no watcher: {:?}", e),
+                    Err(RecvTimeoutError::Timeout) => {
+                        burst = None;
+                        BackupService::perform_auto_backup(&app, game_id, &name, None);
+                    }
                 }
             }
+            println!("Watcher parado para: {}", name);
+        });
+
+        state.unresolved.lock().unwrap().remove(&game_id);
+        let previous = state.watchers.lock().unwrap().insert(game_id, WatchHandle {
+            game_name,
+            paths: watched,
+            sender: tx,
+            thread,
         });
+        // Outra chamada pode ter iniciado um watcher desde o `stop_watching` acima
+        if let Some(previous) = previous {
+            let _ = previous.sender.send(WatchMessage::Stop);
+        }
+        Ok(())
+    }
+
+    /// Criação, escrita, remoção e renomeação (mudanças só de metadados não contam).
+    fn is_change(kind: &EventKind) -> bool {
+        match kind {
+            EventKind::Create(_) | EventKind::Remove(_) => true,
+            EventKind::Modify(ModifyKind::Metadata(_)) => false,
+            EventKind::Modify(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Retorna `false` se o jogo não estava sendo monitorado.
+    pub fn stop_watching(state: &WatcherState, game_id: u32) -> bool {
+        state.unresolved.lock().unwrap().remove(&game_id);
+        match state.watchers.lock().unwrap().remove(&game_id) {
+            Some(handle) => {
+                let _ = handle.sender.send(WatchMessage::Stop);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn list_watching(state: &WatcherState) -> Vec<WatchStatus> {
+        let mut status: Vec<WatchStatus> = state.watchers.lock().unwrap().iter()
+            .map(|(game_id, handle)| WatchStatus {
+                game_id: *game_id,
+                game_name: handle.game_name.clone(),
+                mode: AutoBackupMode::Files,
+                paths: handle.paths.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+                running: !handle.thread.is_finished(),
+                error: None,
+            })
+            .collect();
+        status.extend(state.unresolved.lock().unwrap().values().cloned());
+        status.sort_by(|a, b| a.game_name.cmp(&b.game_name));
+        status
+    }
+
+    /// Para todos os watchers e espera um backup em andamento terminar.
+    pub fn stop_all(state: &WatcherState) {
+        let handles: Vec<WatchHandle> = state.watchers.lock().unwrap().drain().map(|(_, h)| h).collect();
+        for handle in &handles {
+            let _ = handle.sender.send(WatchMessage::Stop);
+        }
+        for handle in handles {
+            let _ = handle.thread.join();
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,171 @@
+// src-tauri/src/services/wrap_service.rs
+use std::io::{BufRead, IsTerminal, Write};
+use std::process::{Command, ExitStatus};
+use tauri::AppHandle;
+use crate::models::RestoreOptions;
+use crate::services::backup_service::BackupService;
+use crate::services::config_service::ConfigService;
+use crate::services::steam_service::SteamService;
+
+/// Modo `wrap %command%` das opções de inicialização da Steam: backup antes
+/// de abrir o jogo e depois que ele fecha, sem abrir a janela do app.
+pub struct WrapService;
+
+impl WrapService {
+    /// Roda o comando do jogo e retorna o código de saída a repassar à Steam.
+    pub fn run(app: AppHandle, command: Vec<String>) -> i32 {
+        if command.is_empty() {
+            eprintln!("Uso: steam-save-manager wrap %command%");
+            return 2;
+        }
+
+        // A Steam define `SteamAppId` para o jogo lançado
+        let game_id = std::env::var("SteamAppId").ok()
+            .and_then(|id| id.parse::<u32>().ok())
+            .filter(|id| *id != 0);
+        let game = game_id.map(|id| (id, Self::game_name(&app, id)));
+
+        let before = game.as_ref().and_then(|(id, name)| Self::backup(&app, *id, name, "Antes da sessão"));
+        if game.is_none() {
+            eprintln!("[save-manager] SteamAppId ausente; rodando o jogo sem backup.");
+        }
+
+        let status = match Self::spawn(&command) {
+            Ok(status) => status,
+            Err(e) => {
+                eprintln!("[save-manager] Falha ao iniciar {}: {}", command[0], e);
+                return 127;
+            }
+        };
+
+        if let Some((game_id, game_name)) = &game {
+            Self::backup(&app, *game_id, game_name, "Após a sessão");
+
+            if !status.success() {
+                if let Some(timestamp) = before {
+                    let question = format!("{} fechou com erro. Restaurar os saves de antes da sessão ({})?", game_name, timestamp);
+                    if Self::confirm(&question) {
+                        match BackupService::restore_backup(app.clone(), *game_id, timestamp, RestoreOptions::default()) {
+                            Ok(report) if report.success => eprintln!("[save-manager] Saves restaurados."),
+                            Ok(report) => {
+                                eprintln!("[save-manager] A restauração falhou.");
+                                for error in report.errors {
+                                    eprintln!("[save-manager] Erro ao restaurar {}: {}", error.target, error.error);
+                                }
+                            }
+                            Err(e) => eprintln!("[save-manager] Erro ao restaurar: {}", e),
+                        }
+                    }
+                }
+            }
+
+            // Só depois da pergunta: a retenção poderia apagar o snapshot de antes da sessão
+            BackupService::apply_retention(&app, *game_id);
+        }
+
+        Self::exit_code(status)
+    }
+
+    fn game_name(app: &AppHandle, game_id: u32) -> String {
+        SteamService::get_game_name(game_id)
+            .or_else(|| ConfigService::load_config(app).auto_backup_games.remove(&game_id))
+            .unwrap_or_else(|| game_id.to_string())
+    }
+
+    /// Retorna o timestamp do snapshot criado.
+    fn backup(app: &AppHandle, game_id: u32, game_name: &str, label: &str) -> Option<String> {
+        let result = BackupService::perform_backup(app.clone(), game_id, game_name.to_string(), Some(label.to_string()));
+        match result.strip_prefix("Sucesso:") {
+            Some(timestamp) => Some(timestamp.to_string()),
+            None => {
+                eprintln!("[save-manager] {} ({}): {}", label, game_name, result);
+                None
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    fn spawn(command: &[String]) -> std::io::Result<ExitStatus> {
+        let mut child = Command::new(&command[0]).args(&command[1..]).spawn()?;
+        signals::forward_to(child.id());
+        let status = child.wait();
+        signals::reset();
+        status
+    }
+
+    #[cfg(not(unix))]
+    fn spawn(command: &[String]) -> std::io::Result<ExitStatus> {
+        Command::new(&command[0]).args(&command[1..]).status()
+    }
+
+    /// Jogo morto por sinal: o wrapper morre pelo mesmo sinal, como se fosse o jogo.
+    #[cfg(unix)]
+    fn exit_code(status: ExitStatus) -> i32 {
+        use std::os::unix::process::ExitStatusExt;
+        match (status.code(), status.signal()) {
+            (Some(code), _) => code,
+            (None, Some(signal)) => {
+                unsafe {
+                    libc::signal(signal, libc::SIG_DFL);
+                    libc::raise(signal);
+                }
+                128 + signal
+            }
+            (None, None) => 1,
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn exit_code(status: ExitStatus) -> i32 {
+        status.code().unwrap_or(1)
+    }
+
+    /// Pergunta via `zenity` (a Steam não dá terminal ao jogo) ou pelo
+    /// terminal; sem nenhum dos dois, não restaura.
+    fn confirm(question: &str) -> bool {
+        let zenity = Command::new("zenity")
+            .args(["--question", "--title=Steam Save Manager", "--no-wrap"])
+            .arg(format!("--text={}", question))
+            .status();
+        if let Ok(status) = zenity {
+            return status.success();
+        }
+
+        if !std::io::stdin().is_terminal() { return false; }
+        eprint!("[save-manager] {} [s/N] ", question);
+        let _ = std::io::stderr().flush();
+        let mut answer = String::new();
+        if std::io::stdin().lock().read_line(&mut answer).is_err() { return false; }
+        matches!(answer.trim().to_lowercase().as_str(), "s" | "sim" | "y" | "yes")
+    }
+}
+
+/// Repassa SIGINT/SIGTERM/SIGHUP/SIGQUIT ao jogo em vez de deixar o
+/// wrapper morrer antes do backup pós-sessão.
+#[cfg(unix)]
+mod signals {
+    use std::sync::atomic::{AtomicI32, Ordering};
+
+    const FORWARDED: [libc::c_int; 4] = [libc::SIGINT, libc::SIGTERM, libc::SIGHUP, libc::SIGQUIT];
+
+    static CHILD_PID: AtomicI32 = AtomicI32::new(0);
+
+    extern "C" fn forward(signal: libc::c_int) {
+        let pid = CHILD_PID.load(Ordering::SeqCst);
+        if pid > 0 {
+            unsafe { libc::kill(pid, signal); }
+        }
+    }
+
+    pub fn forward_to(pid: u32) {
+        CHILD_PID.store(pid as i32, Ordering::SeqCst);
+        let handler: extern "C" fn(libc::c_int) = forward;
+        for signal in FORWARDED {
+            unsafe { libc::signal(signal, handler as libc::sighandler_t); }
+        }
+    }
+
+    pub fn reset() {
+        CHILD_PID.store(0, Ordering::SeqCst);
+    }
+}
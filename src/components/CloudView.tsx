@@ -19,7 +19,7 @@ export function CloudView({ games }: CloudViewProps) {
 
   const selectGame = (game: GameInfo) => {
     setSelectedGame(game);
-    loadBackups(game.name);
+    loadBackups(game.id);
   };
 
   return (
@@ -92,7 +92,7 @@ export function CloudView({ games }: CloudViewProps) {
                         </button>
                       ) : (
                         <button
-                          onClick={() => handleCreateZip(selectedGame.name, bkp.name)}
+                          onClick={() => handleCreateZip(selectedGame.id, bkp.name)}
                           disabled={zipping}
                           className="px-4 py-2 bg-white/5 hover:bg-white/10 border border-white/10 rounded-lg text-xs font-bold uppercase flex items-center gap-2 transition-colors"
                         >
@@ -1,7 +1,7 @@
 import { useState, useEffect } from 'react';
 import { invoke } from "@tauri-apps/api/core";
 import { X, History, RotateCcw, AlertTriangle } from 'lucide-react';
-import { GameInfo, BackupEntry } from '../types';
+import { GameInfo, BackupEntry, RestorePlan, RestoreReport } from '../types';
 import { useAppStore } from '../store/useAppStore';
 
 interface RestoreModalProps {
@@ -14,26 +14,44 @@ export function RestoreModal({ game, onClose, onSuccess }: RestoreModalProps) {
   const [backups, setBackups] = useState<BackupEntry[]>([]);
   const [loading, setLoading] = useState(false);
   const [confirming, setConfirming] = useState<string | null>(null);
+  const [plan, setPlan] = useState<RestorePlan | null>(null);
   const { setFeedback } = useAppStore();
 
   useEffect(() => {
-    invoke<BackupEntry[]>("get_backups", { gameName: game.name })
+    invoke<BackupEntry[]>("get_backups", { gameId: game.id })
       .then(setBackups)
       .catch(console.error);
-  }, [game.name]);
+  }, [game.id]);
+
+  useEffect(() => {
+    setPlan(null);
+    if (!confirming) return;
+    invoke<RestorePlan>("restore_backup_dry_run", {
+      gameId: game.id,
+      timestamp: confirming,
+    })
+      .then(setPlan)
+      .catch(console.error);
+  }, [confirming, game.id]);
+
+  const planFiles = plan?.targets.flatMap((t) => t.files) ?? [];
+  const countAction = (action: string) => planFiles.filter((f) => f.action === action).length;
+  const missingTargets = plan?.targets.filter((t) => !t.exists) ?? [];
 
   const handleRestore = async (timestamp: string) => {
     setLoading(true);
     try {
-      const res = await invoke<string>("restore_backup", {
+      const res = await invoke<RestoreReport>("restore_backup", {
         gameId: game.id,
-        gameName: game.name,
         timestamp,
       });
-      if (res.startsWith("Sucesso")) {
+      if (res.success) {
         setFeedback({ isOpen: true, type: "success", title: "Restaurado!", message: "Seus arquivos foram revertidos." });
         onSuccess();
         onClose();
+      } else {
+        const message = res.errors.map((e) => `${e.target}: ${e.error}`).join("\n");
+        setFeedback({ isOpen: true, type: "error", title: "Nada foi alterado", message });
       }
     } catch (e) {
       setFeedback({ isOpen: true, type: "error", title: "Erro", message: String(e) });
@@ -57,8 +75,11 @@ export function RestoreModal({ game, onClose, onSuccess }: RestoreModalProps) {
           {backups.map((bkp) => (
             <div key={bkp.name} className="flex items-center justify-between p-4 bg-white/5 rounded-xl border border-white/5 hover:border-steam-light/50 transition-all">
               <div className="flex flex-col">
-                <span className="font-bold text-white text-sm">{bkp.name.replace("_", " ")}</span>
-                <span className="text-xs text-gray-500">{bkp.size_mb}</span>
+                <span className="font-bold text-white text-sm">{bkp.label ?? bkp.name.replace("_", " ")}</span>
+                <span className="text-xs text-gray-500">
+                  {bkp.label && `${bkp.name.replace("_", " ")} · `}{bkp.size_mb}{bkp.pinned && " · fixado"}
+                </span>
+                {bkp.note && <span className="text-xs text-gray-400 mt-1">{bkp.note}</span>}
               </div>
               <button
                 disabled={loading}
@@ -77,7 +98,15 @@ export function RestoreModal({ game, onClose, onSuccess }: RestoreModalProps) {
           <div className="bg-[#1b2838] border border-red-500/30 rounded-2xl p-6 max-w-sm text-center">
             <AlertTriangle className="text-red-500 w-12 h-12 mx-auto mb-4" />
             <h3 className="text-lg font-bold text-white mb-2">Tem certeza?</h3>
-            <p className="text-sm text-gray-400 mb-6">Isso substituirá seus saves atuais permanentemente.</p>
+            <p className="text-sm text-gray-400 mb-4">Isso substituirá seus saves atuais permanentemente.</p>
+            {plan && (
+              <div className="text-xs text-gray-400 mb-6 space-y-1">
+                <p>{countAction("create")} novos · {countAction("overwrite")} sobrescritos · {countAction("unchanged")} iguais</p>
+                {missingTargets.map((t) => (
+                  <p key={t.folder} className="text-red-400 truncate">Destino inexistente: {t.target}</p>
+                ))}
+              </div>
+            )}
             <div className="flex gap-3">
               <button onClick={() => setConfirming(null)} className="flex-1 py-2 border border-white/10 rounded-lg text-gray-400">Cancelar</button>
               <button onClick={() => handleRestore(confirming)} className="flex-1 py-2 bg-red-600 rounded-lg font-bold">Sim, Restaurar</button>
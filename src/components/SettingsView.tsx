@@ -1,8 +1,10 @@
 import { useState, useEffect, useRef } from 'react';
-import { Settings, ShieldCheck, Activity, FolderSearch, Loader2, ChevronRight, CheckCircle2 } from 'lucide-react';
+import { Settings, ShieldCheck, Activity, FolderSearch, Loader2, ChevronRight, CheckCircle2, AlertTriangle } from 'lucide-react';
 import { useGames } from '../hooks/useGames';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { useAppStore } from '../store/useAppStore';
+import { AppConfig, GameSession, WatchStatus } from '../types';
 
 export function SettingsView() {
   const { games } = useGames();
@@ -10,10 +12,57 @@ export function SettingsView() {
   
   const [activeWatchers, setActiveWatchers] = useState<Record<number, boolean>>({});
   const [loadingWatchers, setLoadingWatchers] = useState<Record<number, boolean>>({});
+  const [watchErrors, setWatchErrors] = useState<Record<number, string>>({});
+  const [runningGames, setRunningGames] = useState<Record<number, boolean>>({});
+  const [backupRoot, setBackupRoot] = useState<string | null>(null);
+  const [unresolvedFolders, setUnresolvedFolders] = useState<string[]>([]);
   
   const [saveStatus, setSaveStatus] = useState<'idle' | 'saving' | 'saved'>('idle');
   const timerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
 
+  useEffect(() => {
+    const loadStatus = () => invoke<WatchStatus[]>("get_watch_status")
+      .then((status) => {
+        setActiveWatchers(Object.fromEntries(status.map((w) => [w.game_id, w.running])));
+        setWatchErrors(Object.fromEntries(status.filter((w) => w.error).map((w) => [w.game_id, w.error as string])));
+      })
+      .catch(console.error);
+
+    loadStatus();
+    // Watchers salvos são reativados em segundo plano ao abrir o app
+    const unlisten = listen<WatchStatus[]>('auto-backup-restored', () => loadStatus());
+    return () => { unlisten.then((f) => f()); };
+  }, []);
+
+  useEffect(() => {
+    invoke<AppConfig>("load_app_config")
+      .then((config) => setBackupRoot(config.backup_root))
+      .catch(console.error);
+  }, []);
+
+  useEffect(() => {
+    invoke<string[]>("get_unresolved_backup_folders")
+      .then(setUnresolvedFolders)
+      .catch(console.error);
+
+    // A migração das pastas antigas termina em segundo plano
+    const unlisten = listen<string[]>('legacy-backups-unresolved', (e) => setUnresolvedFolders(e.payload));
+    return () => { unlisten.then((f) => f()); };
+  }, []);
+
+  useEffect(() => {
+    invoke<GameSession[]>("get_running_games")
+      .then((sessions) => setRunningGames(Object.fromEntries(sessions.map((s) => [s.game_id, true]))))
+      .catch(console.error);
+
+    const started = listen<GameSession>('game-started', (e) => setRunningGames(p => ({ ...p, [e.payload.game_id]: true })));
+    const stopped = listen<GameSession>('game-stopped', (e) => setRunningGames(p => ({ ...p, [e.payload.game_id]: false })));
+    return () => {
+      started.then((f) => f());
+      stopped.then((f) => f());
+    };
+  }, []);
+
   const handleRetentionChange = (val: number) => {
     const newVal = val < 1 ? 1 : val;
     setRetentionLimit(newVal);
@@ -23,7 +72,7 @@ export function SettingsView() {
 
     timerRef.current = setTimeout(async () => {
       try {
-        await invoke("save_app_config", { retentionLimit: newVal });
+        await invoke("save_app_config", { settings: { retention_limit: newVal } });
         setSaveStatus('saved');
         setTimeout(() => setSaveStatus('idle'), 2000);
       } catch (e) {
@@ -39,6 +88,7 @@ export function SettingsView() {
     try {
       await invoke("toggle_auto_backup", { gameId, gameName, enable: newState });
       setActiveWatchers(p => ({ ...p, [gameId]: newState }));
+      setWatchErrors(p => ({ ...p, [gameId]: '' }));
       setFeedback({
         isOpen: true,
         type: "success",
@@ -80,6 +130,12 @@ export function SettingsView() {
                   <div className="flex flex-col">
                     <span className="text-sm font-bold text-gray-200 group-hover:text-steam-light transition-colors">{game.name}</span>
                     <span className="text-[10px] text-gray-500 font-mono tracking-wider">APPID: {game.id}</span>
+                    {runningGames[game.id] && (
+                      <span className="text-[10px] text-green-400 font-bold uppercase tracking-wider">Em execução</span>
+                    )}
+                    {watchErrors[game.id] && (
+                      <span className="text-[10px] text-red-400">Auto-backup não reativado: {watchErrors[game.id]}</span>
+                    )}
                   </div>
                   <div className="flex items-center gap-3">
                     {isL && <Loader2 size={16} className="text-steam-light animate-spin" />}
@@ -140,9 +196,22 @@ export function SettingsView() {
               <h3 className="font-bold text-xl text-white">Armazenamento Local</h3>
             </div>
             <div className="p-4 bg-black/30 border border-white/5 rounded-2xl flex items-center justify-between">
-              <code className="text-xs text-steam-light truncate">Documents/SaveManagerBackups</code>
+              <code className="text-xs text-steam-light truncate">{backupRoot ? `${backupRoot}/SaveManagerBackups` : 'Documents/SaveManagerBackups'}</code>
               <ChevronRight size={16} className="text-gray-600" />
             </div>
+            {unresolvedFolders.length > 0 && (
+              <div className="mt-4 p-4 bg-yellow-500/10 border border-yellow-500/20 rounded-2xl">
+                <div className="flex items-center gap-2 text-yellow-400 text-xs font-bold mb-2">
+                  <AlertTriangle size={14} />
+                  Pastas de backup sem jogo identificado
+                </div>
+                <ul className="space-y-1">
+                  {unresolvedFolders.map((folder) => (
+                    <li key={folder}><code className="text-xs text-gray-400">{folder}</code></li>
+                  ))}
+                </ul>
+              </div>
+            )}
           </div>
         </div>
       </div>
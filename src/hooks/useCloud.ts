@@ -53,25 +53,25 @@ export function useCloud() {
     });
   };
 
-  const loadBackups = async (gameName: string) => {
+  const loadBackups = async (gameId: number) => {
     try {
-      const backups = await invoke<BackupEntry[]>("get_backups", { gameName });
+      const backups = await invoke<BackupEntry[]>("get_backups", { gameId });
       setCloudBackups(backups);
     } catch (e) {
       console.error("Erro ao carregar backups da nuvem:", e);
     }
   };
 
-  const handleCreateZip = async (gameName: string, timestamp: string) => {
+  const handleCreateZip = async (gameId: number, timestamp: string) => {
     setZipping(true);
     try {
       const res = await invoke<string>("create_zip_for_cloud", {
-        gameName,
+        gameId,
         timestamp: timestamp.toString(),
       });
 
       if (res.startsWith("Sucesso")) {
-        await loadBackups(gameName);
+        await loadBackups(gameId);
         setFeedback({ 
           isOpen: true, 
           type: "success", 
@@ -13,11 +13,12 @@ export function useGames() {
   });
 
   const backupMutation = useMutation({
-    mutationFn: (game: { id: number; name: string }) => 
+    mutationFn: (game: { id: number; name: string; label?: string }) => 
       invoke<string>("backup_game", { 
         gameId: game.id, 
         gameName: game.name,
-        retentionLimit 
+        retentionLimit,
+        label: game.label
       }),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['installed-games'] });
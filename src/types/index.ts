@@ -5,11 +5,219 @@ export interface GameInfo {
   last_backup?: string;
 }
 
+export interface SnapshotFile {
+  path: string;
+  size: number;
+  mtime: number;
+  sha256: string;
+}
+
+export interface SnapshotSource {
+  folder: string;
+  kind: "custom" | "manifest" | "steam_cloud";
+  origin: string;
+  pattern?: string;
+  matched?: string;
+  is_file: boolean;
+  account_id?: number;
+  account_name?: string;
+}
+
+export interface SteamAccount {
+  id3: number;
+  id64: number;
+  account_name?: string;
+  persona_name?: string;
+  most_recent: boolean;
+}
+
+export interface RestoreOptions {
+  source_account?: number;
+  target_account?: number;
+  paths?: string[];
+  destination?: string;
+}
+
+export interface SnapshotTreeNode {
+  name: string;
+  path: string;
+  is_dir: boolean;
+  size: number;
+  mtime: number;
+  children: SnapshotTreeNode[];
+}
+
+export interface FileRules {
+  include: string[];
+  exclude: string[];
+}
+
+export interface SnapshotMetadata {
+  kind: "backup" | "pre_restore";
+  restored_from?: string;
+  label?: string;
+  note?: string;
+  pinned: boolean;
+  app_id: number;
+  game_name: string;
+  app_version?: string;
+  tool_version: string;
+  created_at: string;
+  sources: SnapshotSource[];
+  rules: FileRules;
+  files: SnapshotFile[];
+}
+
 export interface BackupEntry {
   name: string;
   path: string;
   has_zip: boolean;
   size_mb: string;
+  label?: string;
+  note?: string;
+  pinned: boolean;
+  metadata?: SnapshotMetadata;
+}
+
+export interface RestoreTargetError {
+  folder: string;
+  target: string;
+  error: string;
+}
+
+export interface RestoreReport {
+  success: boolean;
+  restored_files: number;
+  targets: string[];
+  errors: RestoreTargetError[];
+  rolled_back: boolean;
+}
+
+export interface PlannedFile {
+  path: string;
+  target: string;
+  action: "create" | "overwrite" | "unchanged";
+  old_size?: number;
+  old_mtime?: number;
+  new_size: number;
+  new_mtime: number;
+}
+
+export interface RestoreTargetPlan {
+  folder: string;
+  target: string;
+  exists: boolean;
+  files: PlannedFile[];
+}
+
+export interface RestorePlan {
+  snapshot: string;
+  targets: RestoreTargetPlan[];
+}
+
+export interface FileDiff {
+  path: string;
+  change: "added" | "removed" | "modified";
+  old_size?: number;
+  new_size?: number;
+  size_delta: number;
+  unified_diff?: string;
+}
+
+export interface BackupDiff {
+  files: FileDiff[];
+  total_size_delta: number;
+}
+
+export interface IntegrityIssues {
+  missing: string[];
+  modified: string[];
+  extra: string[];
+}
+
+export interface VerifyReport {
+  game: string;
+  snapshot: string;
+  files_checked: number;
+  store: IntegrityIssues;
+  zip?: IntegrityIssues;
+  ok: boolean;
+}
+
+export interface VerifyProgress {
+  current: number;
+  total: number;
+  game: string;
+  snapshot: string;
+}
+
+export interface RetentionPolicy {
+  keep_last: number;
+  hourly: number;
+  daily: number;
+  weekly: number;
+}
+
+export interface RetentionDecision {
+  name: string;
+  keep: boolean;
+  reasons: ("last" | "hourly" | "daily" | "weekly" | "pinned" | "pre_restore" | "unreadable")[];
+}
+
+export interface GameStorage {
+  app_id: number;
+  game_name: string;
+  snapshots: number;
+  size: number;
+  reclaimable: number;
+}
+
+export interface StorageReport {
+  games: GameStorage[];
+  total_size: number;
+  store_size: number;
+  per_game_quota?: number;
+  total_quota?: number;
+  reclaimable: number;
+  to_delete: string[];
+}
+
+export type AutoBackupMode = 'files' | 'session';
+
+export interface WatchSettings {
+  mode: AutoBackupMode;
+  quiet_secs: number;
+  max_delay_secs: number;
+  exit_delay_secs: number;
+}
+
+export interface WatchStatus {
+  game_id: number;
+  game_name: string;
+  mode: AutoBackupMode;
+  paths: string[];
+  running: boolean;
+  error: string | null;
+}
+
+export interface GameSession {
+  game_id: number;
+  game_name: string;
+}
+
+export interface AppConfig {
+  retention_limit: number;
+  retention_hourly: number;
+  retention_daily: number;
+  retention_weekly: number;
+  game_quota_mb: number | null;
+  total_quota_mb: number | null;
+  include_config_files: boolean;
+  default_excludes: string[];
+  game_rules: Record<number, FileRules>;
+  auto_backup_games: Record<number, string>;
+  watch_settings: Record<number, WatchSettings>;
+  backup_root: string | null;
 }
 
 export interface GoogleProfile {